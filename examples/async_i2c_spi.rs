@@ -77,7 +77,7 @@ async fn main(_spawner: Spawner) {
     );
 
     let interface = I2CDisplayInterface::new(i2c);
-    let mut display_i2c = Ssd1306Async::new(interface, DisplaySize128x64, DisplayRotation::Rotate0)
+    let display_i2c = Ssd1306Async::new(interface, DisplaySize128x64, DisplayRotation::Rotate0)
         .into_buffered_graphics_mode();
 
     // SPI
@@ -93,19 +93,15 @@ async fn main(_spawner: Spawner) {
         .into_buffered_graphics_mode();
 
     // Init and reset both displays as needed
-    join(
-        async {
-            display_i2c.init().await.unwrap();
-        },
-        async {
+    let (mut display_i2c, mut display_spi) =
+        join(async { display_i2c.init().await.unwrap() }, async {
             display_spi
                 .reset(&mut rst, &mut embassy_time::Delay {})
                 .await
                 .unwrap();
-            display_spi.init().await.unwrap();
-        },
-    )
-    .await;
+            display_spi.init().await.unwrap()
+        })
+        .await;
 
     let raw: ImageRaw<BinaryColor> = ImageRaw::new(include_bytes!("./rust.raw"), 64);
 
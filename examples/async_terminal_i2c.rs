@@ -44,8 +44,10 @@ async fn main(_spawner: Spawner) {
 
     let interface = I2CDisplayInterface::new(i2c);
     let mut display = Ssd1306Async::new(interface, DisplaySize128x64, DisplayRotation::Rotate0)
-        .into_terminal_mode();
-    display.init().await.unwrap();
+        .into_terminal_mode()
+        .init()
+        .await
+        .unwrap();
     let _ = display.clear().await;
 
     /* Endless loop */
@@ -56,7 +56,7 @@ fn main() -> ! {
     display
         .reset(&mut rst, &mut embassy_time::Delay {})
         .unwrap();
-    display.init().unwrap();
+    let mut display = display.init().unwrap();
 
     let yoffset = 20;
 
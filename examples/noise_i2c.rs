@@ -38,8 +38,9 @@ fn main() -> ! {
     );
 
     let interface = I2CDisplayInterface::new(i2c);
-    let mut display = Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0);
-    display.init().unwrap();
+    let mut display = Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0)
+        .init()
+        .unwrap();
 
     let mut buf = [0x00u8; 1024];
 
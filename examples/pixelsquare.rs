@@ -52,7 +52,7 @@ fn main() -> ! {
     display
         .reset(&mut rst, &mut embassy_time::Delay {})
         .unwrap();
-    display.init().unwrap();
+    let mut display = display.init().unwrap();
 
     // Top side
     display.set_pixel(0, 0, true);
@@ -93,7 +93,7 @@ mod app {
         display
             .reset(&mut rst, &mut embassy_time::Delay {})
             .unwrap();
-        display.init().unwrap();
+        let mut display = display.init().unwrap();
 
         // Forget the RST pin to keep the display out of reset
         core::mem::forget(rst);
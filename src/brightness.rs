@@ -50,4 +50,14 @@ impl Brightness {
             contrast,
         }
     }
+
+    /// Get the `phase 2` precharge period.
+    pub const fn precharge(&self) -> u8 {
+        self.precharge
+    }
+
+    /// Get the contrast.
+    pub const fn contrast(&self) -> u8 {
+        self.contrast
+    }
 }
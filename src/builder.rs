@@ -0,0 +1,236 @@
+//! Display builder
+
+use crate::{
+    brightness::Brightness,
+    init_config::InitConfig,
+    mode::{BasicMode, BufferedGraphicsMode, DisplayConfig, TerminalMode},
+    rotation::{DisplayOrientation, DisplayRotation},
+    size::{DisplaySize, NewZeroed},
+    state::Uninitialized,
+    Ssd1306,
+};
+#[cfg(feature = "async")]
+use crate::{
+    mode::{BufferedGraphicsModeAsync, DisplayConfigAsync, TerminalModeAsync},
+    size::DisplaySizeAsync,
+    Ssd1306Async,
+};
+
+/// Builder for an [`Ssd1306`] display.
+///
+/// The builder collects the initial state of the display, which is then set by a single
+/// initialisation sequence. This avoids the flicker and bus traffic of changing it with separate
+/// calls after [`init`](Ssd1306::init).
+///
+/// ```rust
+/// # use ssd1306::test_helpers::StubInterface;
+/// # let interface = StubInterface;
+/// use ssd1306::{prelude::*, Ssd1306Builder};
+///
+/// let mut display = Ssd1306Builder::new(interface, DisplaySize128x64)
+///     .rotation(DisplayRotation::Rotate180)
+///     .mirror(true)
+///     .brightness(Brightness::DIM)
+///     .into_buffered_graphics_mode()
+///     .init()
+///     .unwrap();
+/// display.flush().unwrap();
+/// ```
+///
+/// Displays which must be reset first can be built without initialising them:
+///
+/// ```rust
+/// # use ssd1306::test_helpers::{DelayStub, PinStub, StubInterface};
+/// # let interface = StubInterface;
+/// # let mut reset = PinStub;
+/// # let mut delay = DelayStub;
+/// use ssd1306::{prelude::*, Ssd1306Builder};
+///
+/// let mut display = Ssd1306Builder::new(interface, DisplaySize128x64)
+///     .init_config(InitConfig::ssd1309())
+///     .display_on(false)
+///     .into_terminal_mode()
+///     .build();
+/// display.reset(&mut reset, &mut delay).unwrap();
+///
+/// let mut display = display.init().unwrap();
+/// display.print_char('A').unwrap();
+/// display.set_display_on(true).unwrap();
+/// ```
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(feature = "async", idents(DisplaySize(async = "DisplaySizeAsync")))
+)]
+#[derive(Copy, Clone, Debug)]
+pub struct Ssd1306Builder<DI, SIZE, MODE = BasicMode>
+where
+    SIZE: DisplaySize,
+{
+    interface: DI,
+    size: SIZE,
+    mode: MODE,
+    orientation: DisplayOrientation,
+    config: InitConfig,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(feature = "async", idents(DisplaySize(async = "DisplaySizeAsync")))
+)]
+impl<DI, SIZE> Ssd1306Builder<DI, SIZE, BasicMode>
+where
+    SIZE: DisplaySize,
+{
+    /// Create a builder for a display in [`BasicMode`], which is not rotated, mirrored or inverted
+    /// and is turned on with the default brightness.
+    pub fn new(interface: DI, size: SIZE) -> Self {
+        Self {
+            interface,
+            size,
+            mode: BasicMode,
+            orientation: DisplayRotation::Rotate0.into(),
+            config: InitConfig::new(),
+        }
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(
+        feature = "async",
+        idents(
+            DisplaySize(async = "DisplaySizeAsync"),
+            BufferedGraphicsMode(async = "BufferedGraphicsModeAsync"),
+            TerminalMode(async = "TerminalModeAsync"),
+            Ssd1306(async = "Ssd1306Async"),
+        )
+    )
+)]
+impl<DI, SIZE, MODE> Ssd1306Builder<DI, SIZE, MODE>
+where
+    SIZE: DisplaySize,
+{
+    fn into_mode<MODE2>(self, mode: MODE2) -> Ssd1306Builder<DI, SIZE, MODE2> {
+        Ssd1306Builder {
+            interface: self.interface,
+            size: self.size,
+            mode,
+            orientation: self.orientation,
+            config: self.config,
+        }
+    }
+
+    /// Build a display in [`BufferedGraphicsMode`].
+    pub fn into_buffered_graphics_mode(
+        self,
+    ) -> Ssd1306Builder<DI, SIZE, BufferedGraphicsMode<SIZE>> {
+        self.into_mode(BufferedGraphicsMode::new())
+    }
+
+    /// Build a display in [`BufferedGraphicsMode`] with a shadow buffer.
+    ///
+    /// See [`Ssd1306::into_buffered_graphics_mode_with_shadow`].
+    pub fn into_buffered_graphics_mode_with_shadow(
+        self,
+    ) -> Ssd1306Builder<DI, SIZE, BufferedGraphicsMode<SIZE, SIZE::Buffer>> {
+        self.into_mode(BufferedGraphicsMode::with_shadow(NewZeroed::new_zeroed()))
+    }
+
+    /// Build a display in [`TerminalMode`].
+    pub fn into_terminal_mode(self) -> Ssd1306Builder<DI, SIZE, TerminalMode> {
+        self.into_mode(TerminalMode::new())
+    }
+
+    /// Set the display rotation. Default = [`DisplayRotation::Rotate0`].
+    pub fn rotation(self, rotation: DisplayRotation) -> Self {
+        let orientation = DisplayOrientation::new(rotation, self.orientation.is_mirrored());
+        self.orientation(orientation)
+    }
+
+    /// Mirror the display horizontally. Default = not mirrored.
+    ///
+    /// See [`Ssd1306::set_mirror`].
+    pub fn mirror(self, mirror: bool) -> Self {
+        let orientation = DisplayOrientation::new(self.orientation.rotation(), mirror);
+        self.orientation(orientation)
+    }
+
+    /// Set the display orientation, i.e. both the rotation and mirroring.
+    pub fn orientation(self, orientation: DisplayOrientation) -> Self {
+        Self {
+            orientation,
+            ..self
+        }
+    }
+
+    /// Set the display brightness. Default = [`Brightness::NORMAL`].
+    ///
+    /// The brightness replaces the precharge phase 2 of the [`InitConfig`].
+    pub fn brightness(mut self, brightness: Brightness) -> Self {
+        self.config.brightness = Some(brightness);
+        self
+    }
+
+    /// Invert the screen pixels. Default = not inverted.
+    pub fn invert(mut self, invert: bool) -> Self {
+        self.config.invert = invert;
+        self
+    }
+
+    /// Turn the display on at the end of the initialisation. Default = on.
+    ///
+    /// A display which is kept off can be drawn to and then turned on with
+    /// [`set_display_on`](Ssd1306::set_display_on).
+    pub fn display_on(mut self, on: bool) -> Self {
+        self.config.display_on = on;
+        self
+    }
+
+    /// Set the controller settings sent during initialisation. Default = [`InitConfig::new`].
+    ///
+    /// The brightness, inversion and display on state are part of the [`InitConfig`], so this
+    /// replaces the values set before with [`brightness`](Self::brightness),
+    /// [`invert`](Self::invert) and [`display_on`](Self::display_on).
+    pub fn init_config(self, config: InitConfig) -> Self {
+        Self { config, ..self }
+    }
+
+    /// Build the uninitialised display, e.g. to reset it before it is initialised.
+    ///
+    /// The builder settings are kept in the [`InitConfig`] of the display, so they are applied
+    /// again when the display is initialised again. Changing the brightness, inversion or display
+    /// state at runtime, e.g. with [`set_display_on`](Ssd1306::set_display_on), updates the
+    /// stored settings.
+    pub fn build(self) -> Ssd1306<DI, SIZE, MODE, Uninitialized> {
+        let mut display = Ssd1306::new(self.interface, self.size, self.orientation.rotation())
+            .into_mode(self.mode);
+        display.orientation = self.orientation;
+        display.init_config = self.config;
+        display
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(
+        feature = "async",
+        idents(
+            DisplayConfig(async = "DisplayConfigAsync"),
+            DisplaySize(async = "DisplaySizeAsync"),
+            Ssd1306(async = "Ssd1306Async"),
+        )
+    )
+)]
+impl<DI, SIZE, MODE> Ssd1306Builder<DI, SIZE, MODE>
+where
+    SIZE: DisplaySize,
+    Ssd1306<DI, SIZE, MODE>: DisplayConfig,
+{
+    /// Build the display and initialise it with a single initialisation sequence.
+    #[allow(clippy::type_complexity)]
+    pub async fn init(
+        self,
+    ) -> Result<Ssd1306<DI, SIZE, MODE>, <Ssd1306<DI, SIZE, MODE> as DisplayConfig>::Error> {
+        self.build().init().await
+    }
+}
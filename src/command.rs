@@ -2,9 +2,13 @@
 
 // Shamefully taken from https://github.com/EdgewaterDevelopment/rust-ssd1306
 
+use core::fmt;
 #[cfg(feature = "async")]
 use display_interface::AsyncWriteOnlyDataCommand;
-use display_interface::{DataFormat::U8, DisplayError, WriteOnlyDataCommand};
+use display_interface::{
+    DataFormat::{self, U8},
+    DisplayError, WriteOnlyDataCommand,
+};
 
 /// SSD1306 Commands
 #[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async"))]
@@ -61,9 +65,11 @@ pub enum Command {
     PageStart(Page),
     /// Set display start line from 0-63
     StartLine(u8),
+    /// Set display start line from 0-127. This is the SH1107 replacement of `StartLine`
+    DisplayStartLine(u8),
     /// Reverse columns from 127-0
     SegmentRemap(bool),
-    /// Set multiplex ratio from 15-63 (MUX-1)
+    /// Set multiplex ratio from 15-63 (MUX-1), or up to 127 on the SH1107
     Multiplex(u8),
     /// Scan from COM[n-1] to COM0 (where N is mux ratio)
     ReverseComDir(bool),
@@ -98,102 +104,622 @@ pub enum Command {
     )
 )]
 impl Command {
+    /// Maximum number of bytes a single encoded command occupies.
+    pub const MAX_LEN: usize = 7;
+
     /// Send command to SSD1306
     pub async fn send<DI>(self, iface: &mut DI) -> Result<(), DisplayError>
     where
         DI: WriteOnlyDataCommand,
     {
-        match self {
-            Command::Contrast(val) => Self::send_commands(iface, &[0x81, val]).await,
-            Command::AllOn(on) => Self::send_commands(iface, &[0xA4 | (on as u8)]).await,
-            Command::Invert(inv) => Self::send_commands(iface, &[0xA6 | (inv as u8)]).await,
-            Command::DisplayOn(on) => Self::send_commands(iface, &[0xAE | (on as u8)]).await,
-            Command::HScrollSetup(dir, start, end, rate) => {
-                Self::send_commands(
-                    iface,
-                    &[
-                        0x26 | (dir as u8),
-                        0,
-                        start as u8,
-                        rate as u8,
-                        end as u8,
-                        0,
-                        0xFF,
-                    ],
-                )
-                .await
-            }
-            Command::VHScrollSetup(dir, start, end, rate, offset) => {
-                Self::send_commands(
-                    iface,
-                    &[
-                        0x28 | (dir as u8),
-                        0,
-                        start as u8,
-                        rate as u8,
-                        end as u8,
-                        offset,
-                    ],
-                )
-                .await
-            }
-            Command::EnableScroll(en) => Self::send_commands(iface, &[0x2E | (en as u8)]).await,
-            Command::VScrollArea(above, lines) => {
-                Self::send_commands(iface, &[0xA3, above, lines]).await
+        let (bytes, len) = self.to_bytes();
+
+        iface.send_commands(U8(&bytes[..len])).await
+    }
+
+    /// Encode the command into `out` without sending it, returning the number of bytes written.
+    ///
+    /// Returns [`DisplayError::OutOfBoundsError`] if `out` is too small to hold the command. A
+    /// buffer of [`MAX_LEN`](Self::MAX_LEN) bytes is large enough for any command.
+    ///
+    /// ```rust
+    /// use ssd1306::command::Command;
+    ///
+    /// let mut buf = [0; 8];
+    /// let len = Command::ColumnAddress(0, 127).encode(&mut buf).unwrap();
+    /// assert_eq!(&buf[..len], &[0x21, 0, 127]);
+    /// ```
+    pub fn encode(&self, out: &mut [u8]) -> Result<usize, DisplayError> {
+        let (bytes, len) = self.to_bytes();
+
+        out.get_mut(..len)
+            .ok_or(DisplayError::OutOfBoundsError)?
+            .copy_from_slice(&bytes[..len]);
+
+        Ok(len)
+    }
+
+    /// Opcode and argument bytes of the command, and the number of bytes used.
+    const fn to_bytes(self) -> ([u8; Self::MAX_LEN], usize) {
+        const fn bytes<const L: usize>(data: [u8; L]) -> ([u8; 7], usize) {
+            let mut bytes = [0; 7];
+            let mut i = 0;
+            while i < L {
+                bytes[i] = data[i];
+                i += 1;
             }
-            Command::LowerColStart(addr) => Self::send_commands(iface, &[0xF & addr]).await,
-            Command::UpperColStart(addr) => {
-                Self::send_commands(iface, &[0x10 | (0xF & addr)]).await
+            (bytes, L)
+        }
+
+        match self {
+            Command::Contrast(val) => bytes([0x81, val]),
+            Command::AllOn(on) => bytes([0xA4 | (on as u8)]),
+            Command::Invert(inv) => bytes([0xA6 | (inv as u8)]),
+            Command::DisplayOn(on) => bytes([0xAE | (on as u8)]),
+            Command::HScrollSetup(dir, start, end, rate) => bytes([
+                0x26 | (dir as u8),
+                0,
+                start as u8,
+                rate as u8,
+                end as u8,
+                0,
+                0xFF,
+            ]),
+            Command::VHScrollSetup(dir, start, end, rate, offset) => bytes([
+                0x28 | (dir as u8),
+                0,
+                start as u8,
+                rate as u8,
+                end as u8,
+                offset,
+            ]),
+            Command::EnableScroll(en) => bytes([0x2E | (en as u8)]),
+            Command::VScrollArea(above, lines) => bytes([0xA3, above, lines]),
+            Command::LowerColStart(addr) => bytes([0xF & addr]),
+            Command::UpperColStart(addr) => bytes([0x10 | (0xF & addr)]),
+            Command::ColStart(addr) => bytes([0xF & addr, 0x10 | (0xF & (addr >> 4))]),
+            Command::AddressMode(mode) => bytes([0x20, mode as u8]),
+            Command::ColumnAddress(start, end) => bytes([0x21, start, end]),
+            Command::PageAddress(start, end) => bytes([0x22, start as u8, end as u8]),
+            Command::PageStart(page) => bytes([0xB0 | (page as u8)]),
+            Command::StartLine(line) => bytes([0x40 | (0x3F & line)]),
+            Command::DisplayStartLine(line) => bytes([0xDC, 0x7F & line]),
+            Command::SegmentRemap(remap) => bytes([0xA0 | (remap as u8)]),
+            Command::Multiplex(ratio) => bytes([0xA8, ratio]),
+            Command::ReverseComDir(rev) => bytes([0xC0 | ((rev as u8) << 3)]),
+            Command::DisplayOffset(offset) => bytes([0xD3, offset]),
+            Command::ComPinConfig(alt, lr) => {
+                bytes([0xDA, 0x2 | ((alt as u8) << 4) | ((lr as u8) << 5)])
             }
-            Command::ColStart(addr) => {
-                Self::send_commands(iface, &[0xF & addr, 0x10 | (0xF & (addr >> 4))]).await
+            Command::DisplayClockDiv(fosc, div) => bytes([0xD5, ((0xF & fosc) << 4) | (0xF & div)]),
+            Command::PreChargePeriod(phase1, phase2) => {
+                bytes([0xD9, ((0xF & phase2) << 4) | (0xF & phase1)])
             }
-            Command::AddressMode(mode) => Self::send_commands(iface, &[0x20, mode as u8]).await,
-            Command::ColumnAddress(start, end) => {
-                Self::send_commands(iface, &[0x21, start, end]).await
+            Command::VcomhDeselect(level) => bytes([0xDB, (level as u8) << 4]),
+            Command::Noop => bytes([0xE3]),
+            Command::ChargePump(en) => bytes([0x8D, 0x10 | ((en as u8) << 2)]),
+            Command::InternalIref(en, current) => {
+                bytes([0xAD, ((current as u8) << 5) | ((en as u8) << 4)])
             }
-            Command::PageAddress(start, end) => {
-                Self::send_commands(iface, &[0x22, start as u8, end as u8]).await
+        }
+    }
+}
+
+/// A sequence of encoded commands which can be sent to the display together.
+///
+/// `N` is the capacity of the sequence in bytes. Sequences can be built at compile time with
+/// [`with`](Self::with), for example to keep an initialisation script in flash, or at runtime with
+/// [`push`](Self::push).
+///
+/// ```rust
+/// # use ssd1306::test_helpers::StubInterface;
+/// # let mut interface = StubInterface;
+/// use ssd1306::command::{AddrMode, Command, CommandSequence};
+///
+/// const INIT: CommandSequence<8> = CommandSequence::new()
+///     .with(Command::DisplayOn(false))
+///     .with(Command::AddressMode(AddrMode::Horizontal))
+///     .with(Command::Multiplex(63))
+///     .with(Command::DisplayOn(true));
+///
+/// assert_eq!(INIT.as_bytes(), &[0xAE, 0x20, 0x00, 0xA8, 63, 0xAF]);
+/// INIT.send(&mut interface).unwrap();
+/// ```
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async"))]
+#[derive(Debug, Copy, Clone)]
+pub struct CommandSequence<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(
+        feature = "async",
+        idents(
+            Command(async = "CommandAsync"),
+            CommandBatch(async = "CommandBatchAsync"),
+            WriteOnlyDataCommand(async = "AsyncWriteOnlyDataCommand")
+        )
+    )
+)]
+impl<const N: usize> CommandSequence<N> {
+    /// Create an empty sequence.
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    /// Append a command, returning the extended sequence.
+    ///
+    /// This method is intended for building sequences in `const` context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the command does not fit into the remaining capacity.
+    pub const fn with(self, command: Command) -> Self {
+        let (bytes, len) = command.to_bytes();
+        assert!(self.len + len <= N, "Command sequence capacity exceeded");
+
+        let mut buf = self.buf;
+        let mut i = 0;
+        while i < len {
+            buf[self.len + i] = bytes[i];
+            i += 1;
+        }
+
+        Self {
+            buf,
+            len: self.len + len,
+        }
+    }
+
+    /// Append a command.
+    ///
+    /// Returns [`DisplayError::OutOfBoundsError`] and leaves the sequence unchanged if the
+    /// command does not fit into the remaining capacity.
+    pub fn push(&mut self, command: Command) -> Result<(), DisplayError> {
+        self.len += command.encode(&mut self.buf[self.len..])?;
+
+        Ok(())
+    }
+
+    /// The encoded commands.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Number of bytes in the sequence.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the sequence contains no commands.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all commands from the sequence.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Send all commands in the sequence to the display.
+    ///
+    /// The commands are sent in as few transactions as possible, each holding at most
+    /// [`Command::MAX_LEN`] bytes of whole commands, which is the longest command transaction
+    /// `display-interface-i2c` accepts. Nothing is sent if the sequence is empty.
+    pub async fn send<DI>(&self, iface: &mut DI) -> Result<(), DisplayError>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        let mut batch = CommandBatch::new(iface);
+        let mut bytes = self.as_bytes();
+
+        while let Some(&opcode) = bytes.first() {
+            let (command, rest) = bytes.split_at(command_len(opcode).min(bytes.len()));
+            batch.send_commands(U8(command)).await?;
+            bytes = rest;
+        }
+
+        batch.flush().await
+    }
+}
+
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async"))]
+impl<const N: usize> Default for CommandSequence<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Capacity of a [`CommandBatch`] in bytes.
+///
+/// `display-interface-i2c` copies commands into an 8 byte buffer together with the control byte,
+/// so this is the longest command transaction it accepts.
+const BATCH_LEN: usize = 7;
+
+/// Interface wrapper which coalesces consecutive commands into a single transaction.
+///
+/// Commands are buffered until data is sent, the buffer is full or [`flush`](Self::flush) is
+/// called. Pending commands are not sent automatically when the batch is dropped.
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async"))]
+#[derive(Debug)]
+pub(crate) struct CommandBatch<'a, DI> {
+    iface: &'a mut DI,
+    buf: [u8; BATCH_LEN],
+    len: usize,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(
+        feature = "async",
+        idents(WriteOnlyDataCommand(async = "AsyncWriteOnlyDataCommand"))
+    )
+)]
+impl<'a, DI> CommandBatch<'a, DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    /// Start a new batch on the given interface.
+    pub(crate) fn new(iface: &'a mut DI) -> Self {
+        Self {
+            iface,
+            buf: [0; BATCH_LEN],
+            len: 0,
+        }
+    }
+
+    /// Send all pending commands.
+    pub(crate) async fn flush(&mut self) -> Result<(), DisplayError> {
+        if self.len == 0 {
+            return Ok(());
+        }
+
+        let len = core::mem::replace(&mut self.len, 0);
+        self.iface.send_commands(U8(&self.buf[..len])).await
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(
+        feature = "async",
+        idents(WriteOnlyDataCommand(async = "AsyncWriteOnlyDataCommand"))
+    )
+)]
+impl<DI> WriteOnlyDataCommand for CommandBatch<'_, DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    async fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        let bytes = match cmd {
+            U8(bytes) => bytes,
+            cmd => {
+                self.flush().await?;
+                return self.iface.send_commands(cmd).await;
             }
-            Command::PageStart(page) => Self::send_commands(iface, &[0xB0 | (page as u8)]).await,
-            Command::StartLine(line) => Self::send_commands(iface, &[0x40 | (0x3F & line)]).await,
-            Command::SegmentRemap(remap) => {
-                Self::send_commands(iface, &[0xA0 | (remap as u8)]).await
+        };
+
+        if self.len + bytes.len() > BATCH_LEN {
+            self.flush().await?;
+        }
+
+        if bytes.len() > BATCH_LEN {
+            self.iface.send_commands(U8(bytes)).await
+        } else {
+            self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+            self.len += bytes.len();
+            Ok(())
+        }
+    }
+
+    async fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.flush().await?;
+        self.iface.send_data(buf).await
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(
+        feature = "async",
+        idents(CommandDecoder(async = "CommandDecoderAsync"))
+    )
+)]
+impl Command {
+    /// Decode the command at the start of a raw command byte stream, e.g. taken from a bus
+    /// capture.
+    ///
+    /// Returns the command and the number of bytes it occupies. Note that [`Command::ColStart`]
+    /// is sent as two single byte commands, so it is decoded as a [`Command::LowerColStart`]
+    /// followed by a [`Command::UpperColStart`].
+    ///
+    /// ```rust
+    /// use ssd1306::command::{Command, ParseError};
+    ///
+    /// let (command, len) = Command::parse(&[0x21, 0, 127, 0xAF]).unwrap();
+    /// assert_eq!(len, 3);
+    /// assert_eq!(format!("{}", command), "ColumnAddress(0, 127)");
+    ///
+    /// assert_eq!(
+    ///     Command::parse(&[0x22, 0]).unwrap_err(),
+    ///     ParseError::Truncated {
+    ///         opcode: 0x22,
+    ///         expected: 3,
+    ///         available: 2
+    ///     }
+    /// );
+    /// ```
+    pub fn parse(bytes: &[u8]) -> Result<(Self, usize), ParseError> {
+        let opcode = *bytes.first().ok_or(ParseError::Empty)?;
+
+        let len = command_len(opcode);
+        if bytes.len() < len {
+            return Err(ParseError::Truncated {
+                opcode,
+                expected: len,
+                available: bytes.len(),
+            });
+        }
+        let invalid = |value: u8| ParseError::InvalidArgument { opcode, value };
+        let page = |value: u8| {
+            if value < 16 {
+                Ok(Page::from(value << 3))
+            } else {
+                Err(invalid(value))
             }
-            Command::Multiplex(ratio) => Self::send_commands(iface, &[0xA8, ratio]).await,
-            Command::ReverseComDir(rev) => {
-                Self::send_commands(iface, &[0xC0 | ((rev as u8) << 3)]).await
+        };
+
+        let command = match opcode {
+            0x00..=0x0F => Command::LowerColStart(opcode),
+            0x10..=0x1F => Command::UpperColStart(opcode & 0xF),
+            0x20 => Command::AddressMode(match bytes[1] {
+                0b00 => AddrMode::Horizontal,
+                0b01 => AddrMode::Vertical,
+                0b10 => AddrMode::Page,
+                value => return Err(invalid(value)),
+            }),
+            0x21 => Command::ColumnAddress(bytes[1], bytes[2]),
+            0x22 => Command::PageAddress(page(bytes[1])?, page(bytes[2])?),
+            0x26 | 0x27 => {
+                let dir = if opcode & 1 == 0 {
+                    HScrollDir::LeftToRight
+                } else {
+                    HScrollDir::RightToLeft
+                };
+                Command::HScrollSetup(
+                    dir,
+                    page(bytes[2])?,
+                    page(bytes[4])?,
+                    NFrames::from_bits(bytes[3]).ok_or(invalid(bytes[3]))?,
+                )
             }
-            Command::DisplayOffset(offset) => Self::send_commands(iface, &[0xD3, offset]).await,
-            Command::ComPinConfig(alt, lr) => {
-                Self::send_commands(iface, &[0xDA, 0x2 | ((alt as u8) << 4) | ((lr as u8) << 5)])
-                    .await
+            0x29 | 0x2A => {
+                let dir = if opcode == 0x29 {
+                    VHScrollDir::VerticalRight
+                } else {
+                    VHScrollDir::VerticalLeft
+                };
+                Command::VHScrollSetup(
+                    dir,
+                    page(bytes[2])?,
+                    page(bytes[4])?,
+                    NFrames::from_bits(bytes[3]).ok_or(invalid(bytes[3]))?,
+                    bytes[5],
+                )
             }
-            Command::DisplayClockDiv(fosc, div) => {
-                Self::send_commands(iface, &[0xD5, ((0xF & fosc) << 4) | (0xF & div)]).await
+            0x2E | 0x2F => Command::EnableScroll(opcode & 1 != 0),
+            0x40..=0x7F => Command::StartLine(opcode & 0x3F),
+            0x81 => Command::Contrast(bytes[1]),
+            0x8D => Command::ChargePump(bytes[1] & (1 << 2) != 0),
+            0xA0 | 0xA1 => Command::SegmentRemap(opcode & 1 != 0),
+            0xA3 => Command::VScrollArea(bytes[1], bytes[2]),
+            0xA4 | 0xA5 => Command::AllOn(opcode & 1 != 0),
+            0xA6 | 0xA7 => Command::Invert(opcode & 1 != 0),
+            0xA8 => Command::Multiplex(bytes[1]),
+            0xAD => Command::InternalIref(bytes[1] & (1 << 4) != 0, bytes[1] & (1 << 5) != 0),
+            0xAE | 0xAF => Command::DisplayOn(opcode & 1 != 0),
+            0xB0..=0xBF => Command::PageStart(page(opcode & 0xF)?),
+            0xC0 | 0xC8 => Command::ReverseComDir(opcode & (1 << 3) != 0),
+            0xD3 => Command::DisplayOffset(bytes[1]),
+            0xD5 => Command::DisplayClockDiv(bytes[1] >> 4, bytes[1] & 0xF),
+            0xD9 => Command::PreChargePeriod(bytes[1] & 0xF, bytes[1] >> 4),
+            0xDA => Command::ComPinConfig(bytes[1] & (1 << 4) != 0, bytes[1] & (1 << 5) != 0),
+            0xDC => Command::DisplayStartLine(bytes[1] & 0x7F),
+            0xDB => Command::VcomhDeselect(match bytes[1] >> 4 {
+                0b001 => VcomhLevel::V065,
+                0b010 => VcomhLevel::V077,
+                0b011 => VcomhLevel::V083,
+                0b100 => VcomhLevel::Auto,
+                _ => return Err(invalid(bytes[1])),
+            }),
+            0xE3 => Command::Noop,
+            _ => return Err(ParseError::UnknownOpcode(opcode)),
+        };
+
+        Ok((command, len))
+    }
+
+    /// Decode all commands in a raw command byte stream.
+    ///
+    /// ```rust
+    /// use ssd1306::command::Command;
+    ///
+    /// let trace = [0xAE, 0xD5, 0x80, 0xA8, 0x3F, 0x20, 0x00];
+    /// let decoded = Command::decode(&trace)
+    ///     .map(|command| format!("{}", command.unwrap()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(
+    ///     decoded,
+    ///     [
+    ///         "DisplayOn(false)",
+    ///         "DisplayClockDiv(8, 0)",
+    ///         "Multiplex(63)",
+    ///         "AddressMode(Horizontal)"
+    ///     ]
+    /// );
+    /// ```
+    pub fn decode(bytes: &[u8]) -> CommandDecoder<'_> {
+        CommandDecoder { bytes }
+    }
+}
+
+/// Number of bytes occupied by the command with the given opcode, including the opcode.
+fn command_len(opcode: u8) -> usize {
+    match opcode {
+        0x26 | 0x27 => 7,
+        0x29 | 0x2A => 6,
+        0x21 | 0x22 | 0xA3 => 3,
+        0x20 | 0x81 | 0x8D | 0xA8 | 0xAD | 0xD3 | 0xD5 | 0xD9 | 0xDA | 0xDB | 0xDC => 2,
+        _ => 1,
+    }
+}
+
+/// Iterator over the commands in a raw command byte stream.
+///
+/// Created by [`Command::decode`]. An unknown opcode or invalid argument is reported as an error
+/// and decoding continues after the offending command. A truncated command is reported as an error
+/// and ends the iteration.
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async"))]
+#[derive(Debug, Clone)]
+pub struct CommandDecoder<'a> {
+    bytes: &'a [u8],
+}
+
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(feature = "async", idents(Command(async = "CommandAsync")))
+)]
+impl<'a> CommandDecoder<'a> {
+    /// The bytes that have not been decoded yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(feature = "async", idents(Command(async = "CommandAsync")))
+)]
+impl<'a> Iterator for CommandDecoder<'a> {
+    type Item = Result<Command, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+
+        let result = Command::parse(self.bytes);
+        let consumed = match result {
+            Ok((_, len)) => len,
+            Err(ParseError::InvalidArgument { opcode, .. }) => command_len(opcode),
+            Err(ParseError::Truncated { .. }) => self.bytes.len(),
+            Err(_) => 1,
+        };
+        self.bytes = &self.bytes[consumed..];
+
+        Some(result.map(|(command, _)| command))
+    }
+}
+
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async"))]
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Command::Contrast(val) => write!(f, "Contrast({})", val),
+            Command::AllOn(on) => write!(f, "AllOn({})", on),
+            Command::Invert(inv) => write!(f, "Invert({})", inv),
+            Command::DisplayOn(on) => write!(f, "DisplayOn({})", on),
+            Command::HScrollSetup(dir, start, end, rate) => write!(
+                f,
+                "HScrollSetup({:?}, {}, {}, {:?})",
+                dir, start as u8, end as u8, rate
+            ),
+            Command::VHScrollSetup(dir, start, end, rate, offset) => write!(
+                f,
+                "VHScrollSetup({:?}, {}, {}, {:?}, {})",
+                dir, start as u8, end as u8, rate, offset
+            ),
+            Command::EnableScroll(en) => write!(f, "EnableScroll({})", en),
+            Command::VScrollArea(above, lines) => write!(f, "VScrollArea({}, {})", above, lines),
+            Command::LowerColStart(addr) => write!(f, "LowerColStart({})", addr),
+            Command::UpperColStart(addr) => write!(f, "UpperColStart({})", addr),
+            Command::ColStart(addr) => write!(f, "ColStart({})", addr),
+            Command::AddressMode(mode) => write!(f, "AddressMode({:?})", mode),
+            Command::ColumnAddress(start, end) => write!(f, "ColumnAddress({}, {})", start, end),
+            Command::PageAddress(start, end) => {
+                write!(f, "PageAddress({}, {})", start as u8, end as u8)
             }
+            Command::PageStart(page) => write!(f, "PageStart({})", page as u8),
+            Command::StartLine(line) => write!(f, "StartLine({})", line),
+            Command::DisplayStartLine(line) => write!(f, "DisplayStartLine({})", line),
+            Command::SegmentRemap(remap) => write!(f, "SegmentRemap({})", remap),
+            Command::Multiplex(ratio) => write!(f, "Multiplex({})", ratio),
+            Command::ReverseComDir(rev) => write!(f, "ReverseComDir({})", rev),
+            Command::DisplayOffset(offset) => write!(f, "DisplayOffset({})", offset),
+            Command::ComPinConfig(alt, lr) => write!(f, "ComPinConfig({}, {})", alt, lr),
+            Command::DisplayClockDiv(fosc, div) => write!(f, "DisplayClockDiv({}, {})", fosc, div),
             Command::PreChargePeriod(phase1, phase2) => {
-                Self::send_commands(iface, &[0xD9, ((0xF & phase2) << 4) | (0xF & phase1)]).await
-            }
-            Command::VcomhDeselect(level) => {
-                Self::send_commands(iface, &[0xDB, (level as u8) << 4]).await
-            }
-            Command::Noop => Self::send_commands(iface, &[0xE3]).await,
-            Command::ChargePump(en) => {
-                Self::send_commands(iface, &[0x8D, 0x10 | ((en as u8) << 2)]).await
-            }
-            Command::InternalIref(en, current) => {
-                Self::send_commands(iface, &[0xAD, ((current as u8) << 5) | ((en as u8) << 4)])
-                    .await
+                write!(f, "PreChargePeriod({}, {})", phase1, phase2)
             }
+            Command::VcomhDeselect(level) => write!(f, "VcomhDeselect({:?})", level),
+            Command::Noop => write!(f, "Noop"),
+            Command::ChargePump(en) => write!(f, "ChargePump({})", en),
+            Command::InternalIref(en, current) => write!(f, "InternalIref({}, {})", en, current),
         }
     }
+}
 
-    async fn send_commands<DI>(iface: &mut DI, data: &[u8]) -> Result<(), DisplayError>
-    where
-        DI: WriteOnlyDataCommand,
-    {
-        iface.send_commands(U8(data)).await
+/// Errors which can occur when decoding a command byte stream
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// There were no bytes to decode
+    Empty,
+    /// The byte stream ended before all argument bytes of a command
+    Truncated {
+        /// The opcode of the incomplete command
+        opcode: u8,
+        /// The number of bytes the command occupies, including the opcode
+        expected: usize,
+        /// The number of bytes that were available
+        available: usize,
+    },
+    /// The byte is not a known command opcode
+    UnknownOpcode(u8),
+    /// An argument byte does not correspond to a valid value for the command
+    InvalidArgument {
+        /// The opcode of the command
+        opcode: u8,
+        /// The offending argument byte
+        value: u8,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no command bytes"),
+            Self::Truncated {
+                opcode,
+                expected,
+                available,
+            } => write!(
+                f,
+                "command 0x{:02X} needs {} bytes, but only {} are available",
+                opcode, expected, available
+            ),
+            Self::UnknownOpcode(opcode) => write!(f, "unknown opcode 0x{:02X}", opcode),
+            Self::InvalidArgument { opcode, value } => write!(
+                f,
+                "invalid argument 0x{:02X} for command 0x{:02X}",
+                value, opcode
+            ),
+        }
     }
 }
 
@@ -300,6 +826,22 @@ pub enum NFrames {
     F256 = 0b011,
 }
 
+impl NFrames {
+    fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0b111 => Some(NFrames::F2),
+            0b100 => Some(NFrames::F3),
+            0b101 => Some(NFrames::F4),
+            0b000 => Some(NFrames::F5),
+            0b110 => Some(NFrames::F25),
+            0b001 => Some(NFrames::F64),
+            0b010 => Some(NFrames::F128),
+            0b011 => Some(NFrames::F256),
+            _ => None,
+        }
+    }
+}
+
 /// Address mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
@@ -313,7 +855,7 @@ pub enum AddrMode {
 }
 
 /// Vcomh Deselect level
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
 pub enum VcomhLevel {
     /// 0.65 * Vcc
@@ -0,0 +1,500 @@
+//! Display controllers.
+//!
+//! A [`Controller`] describes the display driver chip: how it is initialised, how a rectangular
+//! window of its RAM is addressed and how data is streamed into that window. The display modes
+//! only talk to the display through the controller of the [`DisplaySize`], so they work with all
+//! SSD1306 compatible drivers.
+//!
+//! The default methods implement the SSD1306 command set. A controller for another driver only
+//! needs to override the methods where the driver differs:
+//!
+//! ```rust
+//! use display_interface::{DisplayError, WriteOnlyDataCommand};
+//! use ssd1306::{
+//!     command::Command,
+//!     controller::Controller,
+//!     prelude::*,
+//!     Ssd1306,
+//! };
+//! # use ssd1306::test_helpers::StubInterface;
+//!
+//! /// A driver which needs the display to be inverted after each orientation change
+//! #[derive(Debug, Default, Copy, Clone)]
+//! struct InvertedController;
+//!
+//! impl Controller for InvertedController {
+//!     fn set_scan_direction(
+//!         &mut self,
+//!         iface: &mut impl WriteOnlyDataCommand,
+//!         remap: bool,
+//!         reverse: bool,
+//!     ) -> Result<(), DisplayError> {
+//!         Command::SegmentRemap(remap).send(iface)?;
+//!         Command::ReverseComDir(reverse).send(iface)?;
+//!         Command::Invert(true).send(iface)
+//!     }
+//! }
+//!
+//! #[derive(Debug, Copy, Clone)]
+//! struct InvertedDisplay;
+//!
+//! impl DisplaySize for InvertedDisplay {
+//!     const WIDTH: u8 = 128;
+//!     const HEIGHT: u8 = 64;
+//!     type Controller = InvertedController;
+//!     type Buffer = [u8; 128 * 64 / 8];
+//!
+//!     fn configure(&self, iface: &mut impl WriteOnlyDataCommand) -> Result<(), DisplayError> {
+//!         Command::ComPinConfig(true, false).send(iface)
+//!     }
+//! }
+//!
+//! # let interface = StubInterface;
+//! let display = Ssd1306::new(interface, InvertedDisplay, DisplayRotation::Rotate0)
+//!     .into_buffered_graphics_mode()
+//!     .init()
+//!     .unwrap();
+//! ```
+
+use crate::{
+    brightness::Brightness,
+    command::{AddrMode, Command, CommandBatch},
+    init_config::InitConfig,
+    rotation::DisplayOrientation,
+    size::DisplaySize,
+};
+#[cfg(feature = "async")]
+use crate::{
+    command::{CommandAsync, CommandBatchAsync},
+    size::DisplaySizeAsync,
+};
+use core::fmt::Debug;
+#[cfg(feature = "async")]
+use display_interface::AsyncWriteOnlyDataCommand;
+use display_interface::{DataFormat::U8, DisplayError, WriteOnlyDataCommand};
+
+/// Display driver chip.
+///
+/// The controller is created with [`Default`] when the display is created and can keep state
+/// between calls, e.g. to emulate an addressing mode the driver does not support.
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(
+        feature = "async",
+        idents(
+            Command(async = "CommandAsync"),
+            CommandBatch(async = "CommandBatchAsync"),
+            DisplaySize(async = "DisplaySizeAsync"),
+            WriteOnlyDataCommand(async = "AsyncWriteOnlyDataCommand"),
+        )
+    )
+)]
+pub trait Controller: Debug + Default + Copy {
+    /// Whether the driver has the internal charge pump of the SSD1306
+    ///
+    /// If not, the charge pump command is not sent and
+    /// [`InitConfig::charge_pump`](crate::prelude::InitConfig::charge_pump) has no effect.
+    const CHARGE_PUMP: bool = true;
+
+    /// Whether the driver encodes the VCOMH deselect level like the SSD1306
+    ///
+    /// If not, the VCOMH command is not sent, so the level stays at its reset value, and
+    /// [`InitConfig::vcomh`](crate::prelude::InitConfig::vcomh) has no effect.
+    const VCOMH_LEVELS: bool = true;
+
+    /// Whether the driver only supports page addressing, like the SH1106
+    ///
+    /// The SSD1306 specific addressing mode and scrolling commands are then not sent during
+    /// initialisation.
+    const PAGE_ADDRESSING_ONLY: bool = false;
+
+    /// Whether the driver RAM columns are driven by the common outputs and its rows by the segment
+    /// outputs, like the SH1107
+    ///
+    /// The multiplex ratio, display offset and COM scan direction then apply to the columns, and
+    /// the segment remap to the rows. The multiplexed commons are centered in the driver outputs,
+    /// so the display offset is adjusted to start the display at column 0 of the RAM.
+    const TRANSPOSED_RAM: bool = false;
+
+    /// Initialise the display in the given addressing mode.
+    ///
+    /// All initialisation commands are sent to the display in a single transaction. Controllers
+    /// which override this method can read the settings of the `config` with its `get_*` methods.
+    ///
+    /// Returns [`DisplayError::OutOfBoundsError`] without sending any command if the display
+    /// offset or start line of the `config` is outside the [`DisplaySize::DRIVER_ROWS`].
+    async fn init<SIZE: DisplaySize>(
+        &mut self,
+        iface: &mut impl WriteOnlyDataCommand,
+        size: &SIZE,
+        config: &InitConfig,
+        orientation: DisplayOrientation,
+        mode: AddrMode,
+    ) -> Result<(), DisplayError> {
+        if config.display_offset >= SIZE::DRIVER_ROWS || config.start_line >= SIZE::DRIVER_ROWS {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+
+        let mut batch = CommandBatch::new(&mut *iface);
+
+        Command::DisplayOn(false).send(&mut batch).await?;
+        Command::DisplayClockDiv(config.oscillator_frequency, config.divide_ratio)
+            .send(&mut batch)
+            .await?;
+        if Self::TRANSPOSED_RAM {
+            // Move the first RAM column of the display to the first of the centered commons
+            let first_com = (SIZE::DRIVER_COLS - size.width()) / 2;
+            let offset = config
+                .display_offset
+                .wrapping_add(size.offset_x())
+                .wrapping_add(SIZE::DRIVER_COLS - first_com)
+                % SIZE::DRIVER_COLS;
+
+            Command::Multiplex(size.width() - 1)
+                .send(&mut batch)
+                .await?;
+            Command::DisplayOffset(offset).send(&mut batch).await?;
+            Command::DisplayStartLine(config.start_line)
+                .send(&mut batch)
+                .await?;
+        } else {
+            Command::Multiplex(size.height() - 1)
+                .send(&mut batch)
+                .await?;
+            Command::DisplayOffset(config.display_offset)
+                .send(&mut batch)
+                .await?;
+            Command::StartLine(config.start_line)
+                .send(&mut batch)
+                .await?;
+        }
+        if Self::CHARGE_PUMP {
+            if let Some(enable) = config.charge_pump {
+                Command::ChargePump(enable).send(&mut batch).await?;
+            }
+        }
+        if !Self::PAGE_ADDRESSING_ONLY {
+            Command::AddressMode(mode).send(&mut batch).await?;
+        }
+
+        size.configure(&mut batch).await?;
+        if let Some((alternative, lr_remap)) = config.com_pins {
+            Command::ComPinConfig(alternative, lr_remap)
+                .send(&mut batch)
+                .await?;
+        }
+        self.set_orientation(&mut batch, orientation).await?;
+
+        let (precharge_phase2, contrast) = match config.brightness {
+            Some(brightness) => (brightness.precharge, brightness.contrast),
+            None => (config.precharge_phase2, Brightness::default().contrast),
+        };
+        Command::PreChargePeriod(config.precharge_phase1, precharge_phase2)
+            .send(&mut batch)
+            .await?;
+        Command::Contrast(contrast).send(&mut batch).await?;
+        if Self::VCOMH_LEVELS {
+            if let Some(level) = config.vcomh {
+                Command::VcomhDeselect(level).send(&mut batch).await?;
+            }
+        }
+        Command::AllOn(false).send(&mut batch).await?;
+        Command::Invert(config.invert).send(&mut batch).await?;
+        if !Self::PAGE_ADDRESSING_ONLY {
+            Command::EnableScroll(false).send(&mut batch).await?;
+        }
+        if config.display_on {
+            Command::DisplayOn(true).send(&mut batch).await?;
+        }
+        batch.flush().await?;
+
+        if Self::PAGE_ADDRESSING_ONLY {
+            self.set_addr_mode::<SIZE>(iface, mode).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Change the addressing mode.
+    async fn set_addr_mode<SIZE: DisplaySize>(
+        &mut self,
+        iface: &mut impl WriteOnlyDataCommand,
+        mode: AddrMode,
+    ) -> Result<(), DisplayError> {
+        Command::AddressMode(mode).send(iface).await
+    }
+
+    /// Set the RAM area written by [`write_data`](Self::write_data), from the `start` column and
+    /// row up to but not including the `end` column and row.
+    ///
+    /// In page addressing mode only the start point is set.
+    async fn set_draw_area(
+        &mut self,
+        iface: &mut impl WriteOnlyDataCommand,
+        mode: AddrMode,
+        start: (u8, u8),
+        end: (u8, u8),
+    ) -> Result<(), DisplayError> {
+        let mut batch = CommandBatch::new(iface);
+
+        Command::ColumnAddress(start.0, end.0.saturating_sub(1))
+            .send(&mut batch)
+            .await?;
+
+        if mode != AddrMode::Page {
+            Command::PageAddress(start.1.into(), (end.1.saturating_sub(1)).into())
+                .send(&mut batch)
+                .await?;
+        }
+
+        batch.flush().await
+    }
+
+    /// Write data to the display RAM at the current position and advance the position
+    /// accordingly.
+    async fn write_data(
+        &mut self,
+        iface: &mut impl WriteOnlyDataCommand,
+        data: &[u8],
+    ) -> Result<(), DisplayError> {
+        iface.send_data(U8(data)).await
+    }
+
+    /// Set the segment remap and COM scan direction for the display orientation.
+    async fn set_orientation(
+        &mut self,
+        iface: &mut impl WriteOnlyDataCommand,
+        orientation: DisplayOrientation,
+    ) -> Result<(), DisplayError> {
+        let (remap, reverse) = orientation.scan_direction();
+
+        self.set_scan_direction(iface, remap, reverse).await
+    }
+
+    /// Set the segment remap and COM scan direction, given for SSD1306 style drivers.
+    async fn set_scan_direction(
+        &mut self,
+        iface: &mut impl WriteOnlyDataCommand,
+        remap: bool,
+        reverse: bool,
+    ) -> Result<(), DisplayError> {
+        // The segments and commons of transposed drivers flip the other axis
+        let (remap, reverse) = if Self::TRANSPOSED_RAM {
+            (reverse, remap)
+        } else {
+            (remap, reverse)
+        };
+
+        Command::SegmentRemap(remap).send(iface).await?;
+        Command::ReverseComDir(reverse).send(iface).await
+    }
+}
+
+/// SSD1306 controller
+///
+/// Also used for command compatible drivers with a charge pump like the SSD1315, and the SSD1309
+/// when initialised with [`InitConfig::ssd1309`].
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Ssd1306Controller;
+
+/// SSD1305 controller
+///
+/// The SSD1305 is command compatible with the SSD1306, but has no internal charge pump and encodes
+/// the VCOMH deselect level differently, so the level is left at its reset value. Its area colour
+/// and low power display commands are not supported, so the panel is driven in monochrome mode,
+/// which is the reset default.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Ssd1305Controller;
+
+/// SH1106 controller
+///
+/// The SH1106 has no horizontal or vertical addressing mode. Horizontal addressing is emulated by
+/// writing the display page by page, vertical addressing is not supported. Its DC-DC converter is
+/// enabled after reset and is not controlled by [`InitConfig::charge_pump`].
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Sh1106Controller {
+    window: Option<PageWindow>,
+}
+
+/// SH1107 controller
+///
+/// The SH1107 addresses its RAM like the [`Sh1106Controller`], but its RAM columns are driven by
+/// the common outputs. See [`Controller::TRANSPOSED_RAM`].
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Sh1107Controller(Sh1106Controller);
+
+/// Horizontal addressing window emulated for drivers that only support page addressing.
+#[derive(Copy, Clone, Debug)]
+struct PageWindow {
+    columns: (u8, u8),
+    pages: (u8, u8),
+    column: u8,
+    page: u8,
+}
+
+impl PageWindow {
+    fn new(columns: (u8, u8), pages: (u8, u8)) -> Self {
+        Self {
+            columns,
+            pages,
+            column: columns.0,
+            page: pages.0,
+        }
+    }
+
+    /// Number of bytes left in the current page of the window.
+    fn remaining(&self) -> usize {
+        usize::from(self.columns.1 - self.column) + 1
+    }
+
+    /// Advance by `len` bytes written to the current page, moving on to the start of the next
+    /// page at the end of the window.
+    fn advance(&mut self, len: usize) {
+        if len < self.remaining() {
+            self.column += len as u8;
+        } else {
+            self.column = self.columns.0;
+            self.page = if self.page >= self.pages.1 {
+                self.pages.0
+            } else {
+                self.page + 1
+            };
+        }
+    }
+}
+
+maybe_async_cfg::content! {
+#![maybe_async_cfg::default(
+        idents(
+                WriteOnlyDataCommand(sync, async = "AsyncWriteOnlyDataCommand"),
+                Command(sync, async = "CommandAsync"),
+                CommandBatch(sync, async = "CommandBatchAsync"),
+                Controller(sync, async = "ControllerAsync"),
+                DisplaySize(sync, async = "DisplaySizeAsync")
+        )
+)]
+
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async", keep_self))]
+impl Controller for Ssd1306Controller {}
+
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async", keep_self))]
+impl Controller for Ssd1305Controller {
+    const CHARGE_PUMP: bool = false;
+    const VCOMH_LEVELS: bool = false;
+}
+
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async", keep_self))]
+impl Controller for Sh1106Controller {
+    const CHARGE_PUMP: bool = false;
+    const PAGE_ADDRESSING_ONLY: bool = true;
+
+    async fn set_addr_mode<SIZE: DisplaySize>(
+        &mut self,
+        _iface: &mut impl WriteOnlyDataCommand,
+        mode: AddrMode,
+    ) -> Result<(), DisplayError> {
+        self.window = match mode {
+            AddrMode::Page => None,
+            AddrMode::Horizontal => Some(PageWindow::new(
+                (0, SIZE::DRIVER_COLS - 1),
+                (0, SIZE::DRIVER_ROWS / 8 - 1),
+            )),
+            AddrMode::Vertical => return Err(DisplayError::InvalidFormatError),
+        };
+        Ok(())
+    }
+
+    /// The area is only taken into account in horizontal addressing mode, otherwise just the
+    /// start point is set.
+    ///
+    /// Returns [`DisplayError::OutOfBoundsError`] if the area is empty in horizontal addressing
+    /// mode.
+    async fn set_draw_area(
+        &mut self,
+        iface: &mut impl WriteOnlyDataCommand,
+        _mode: AddrMode,
+        start: (u8, u8),
+        end: (u8, u8),
+    ) -> Result<(), DisplayError> {
+        if self.window.is_some() {
+            if end.0 <= start.0 || end.1 <= start.1 {
+                return Err(DisplayError::OutOfBoundsError);
+            }
+            self.window = Some(PageWindow::new(
+                (start.0, end.0.saturating_sub(1)),
+                (start.1 / 8, end.1.saturating_sub(1) / 8),
+            ));
+            return Ok(());
+        }
+
+        let mut batch = CommandBatch::new(iface);
+        Command::ColStart(start.0).send(&mut batch).await?;
+        Command::PageStart(start.1.into()).send(&mut batch).await?;
+        batch.flush().await
+    }
+
+    async fn write_data(
+        &mut self,
+        iface: &mut impl WriteOnlyDataCommand,
+        mut data: &[u8],
+    ) -> Result<(), DisplayError> {
+        let window = match &mut self.window {
+            Some(window) => window,
+            None => return iface.send_data(U8(data)).await,
+        };
+
+        while !data.is_empty() {
+            // Move to the next page of the window
+            if window.column == window.columns.0 {
+                let mut batch = CommandBatch::new(&mut *iface);
+                Command::ColStart(window.column).send(&mut batch).await?;
+                Command::PageStart((window.page * 8).into())
+                    .send(&mut batch)
+                    .await?;
+                batch.flush().await?;
+            }
+
+            let (chunk, rest) = data.split_at(data.len().min(window.remaining()));
+            iface.send_data(U8(chunk)).await?;
+            window.advance(chunk.len());
+            data = rest;
+        }
+
+        Ok(())
+    }
+}
+
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async", keep_self))]
+impl Controller for Sh1107Controller {
+    const CHARGE_PUMP: bool = false;
+    const PAGE_ADDRESSING_ONLY: bool = true;
+    const TRANSPOSED_RAM: bool = true;
+
+    async fn set_addr_mode<SIZE: DisplaySize>(
+        &mut self,
+        iface: &mut impl WriteOnlyDataCommand,
+        mode: AddrMode,
+    ) -> Result<(), DisplayError> {
+        Controller::set_addr_mode::<SIZE>(&mut self.0, iface, mode).await
+    }
+
+    async fn set_draw_area(
+        &mut self,
+        iface: &mut impl WriteOnlyDataCommand,
+        mode: AddrMode,
+        start: (u8, u8),
+        end: (u8, u8),
+    ) -> Result<(), DisplayError> {
+        Controller::set_draw_area(&mut self.0, iface, mode, start, end).await
+    }
+
+    async fn write_data(
+        &mut self,
+        iface: &mut impl WriteOnlyDataCommand,
+        data: &[u8],
+    ) -> Result<(), DisplayError> {
+        Controller::write_data(&mut self.0, iface, data).await
+    }
+}
+
+} // content
@@ -0,0 +1,558 @@
+//! Host-side SSD1306 emulator.
+//!
+//! [`Emulator`] is a display interface that, instead of talking to real hardware, models the
+//! SSD1306 controller: it decodes the command stream with [`Command::decode`], keeps track of the
+//! GDDRAM address pointer in page, horizontal and vertical addressing modes and stores written
+//! data in its own 128x64 GDDRAM. Drivers with a 132 column RAM and page addressing only, like the
+//! SH1106, and drivers with a 128x128 RAM scanned along its columns, like the SH1107, are modelled
+//! too. The image the panel would show can then be read back with [`Emulator::pixel`], which makes
+//! it possible to check the output of the driver without a display attached.
+//!
+//! This module is only available with the `emulator` feature enabled.
+//!
+//! ```rust
+//! use embedded_graphics::{
+//!     pixelcolor::BinaryColor,
+//!     prelude::*,
+//!     primitives::{PrimitiveStyle, Rectangle},
+//! };
+//! use ssd1306::{emulator::Emulator, prelude::*, Ssd1306};
+//!
+//! let mut display = Ssd1306::new(
+//!     Emulator::for_size(DisplaySize128x32),
+//!     DisplaySize128x32,
+//!     DisplayRotation::Rotate0,
+//! )
+//! .into_buffered_graphics_mode()
+//! .init()
+//! .unwrap();
+//!
+//! Rectangle::new(Point::new(10, 5), Size::new(4, 3))
+//!     .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+//!     .draw(&mut display)
+//!     .unwrap();
+//! display.flush().unwrap();
+//!
+//! let emulator = display.release();
+//! assert!(emulator.pixel(10, 5));
+//! assert!(emulator.pixel(13, 7));
+//! assert!(!emulator.pixel(14, 7));
+//! assert!(!emulator.pixel(10, 8));
+//! ```
+
+use crate::{
+    command::{AddrMode, Command},
+    controller::Controller,
+    size::{DisplaySize, DisplaySize128x64},
+};
+#[cfg(feature = "async")]
+use display_interface::AsyncWriteOnlyDataCommand;
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+
+/// Number of columns (segments) in the SSD1306 GDDRAM.
+const RAM_COLS: u8 = 128;
+
+/// Number of rows (commons) in the SSD1306 GDDRAM.
+const RAM_ROWS: u8 = 64;
+
+/// Largest number of columns in the GDDRAM of any supported driver.
+const MAX_RAM_COLS: u8 = 132;
+
+/// Largest number of 8 pixel high pages in the GDDRAM of any supported driver.
+const MAX_RAM_PAGES: u8 = 16;
+
+/// Software model of an SSD1306 controller and the panel attached to it.
+///
+/// The emulator implements [`WriteOnlyDataCommand`] (and `AsyncWriteOnlyDataCommand` with the
+/// `async` feature enabled) so it can be passed to [`Ssd1306::new`](crate::Ssd1306::new) in place
+/// of a real interface.
+///
+/// The panel is described by its size in pixels and the offset of its top left corner in the
+/// controller's segment and common outputs, exactly like the constants of a
+/// [`DisplaySize`]. Pixel coordinates passed to [`pixel`](Self::pixel) are panel coordinates,
+/// oriented so that a display driven with [`DisplayRotation::Rotate0`] appears upright.
+///
+/// The following commands change the emulated state: `AddressMode`, `ColumnAddress`,
+/// `PageAddress`, `ColStart`, `PageStart`, `SegmentRemap`, `ReverseComDir`, `Multiplex`,
+/// `StartLine`, `DisplayOffset`, `Invert`, `AllOn` and `DisplayOn`. All other commands are decoded
+/// and checked for validity, but have no visible effect. Emulators created with
+/// [`for_size`](Self::for_size) for a [`DisplaySize`] whose [`Controller`] has
+/// [`PAGE_ADDRESSING_ONLY`](Controller::PAGE_ADDRESSING_ONLY) set reject the SSD1306 specific
+/// addressing and scrolling commands, and without [`CHARGE_PUMP`](Controller::CHARGE_PUMP) the
+/// charge pump command.
+///
+/// If the [`Controller`] has [`TRANSPOSED_RAM`](Controller::TRANSPOSED_RAM) set, the GDDRAM
+/// columns are driven by the common outputs and the rows by the segment outputs. As on the SH1107,
+/// the commons selected by the multiplex ratio are then centered in the driver outputs and only the
+/// `DisplayStartLine` command sets the start line. The interaction of `Multiplex`,
+/// `DisplayOffset` and `StartLine` is simplified: the row counter driving a common output is
+/// shifted by the display offset, then by the start line.
+///
+/// [`DisplayRotation::Rotate0`]: crate::rotation::DisplayRotation::Rotate0
+#[derive(Clone, Debug)]
+#[allow(missing_copy_implementations)]
+pub struct Emulator {
+    gddram: [u8; MAX_RAM_COLS as usize * MAX_RAM_PAGES as usize],
+    ram_cols: u8,
+    ram_pages: u8,
+    page_addressing_only: bool,
+    charge_pump: bool,
+    transposed: bool,
+    addr_mode: AddrMode,
+    column: u8,
+    page: u8,
+    column_start: u8,
+    column_end: u8,
+    page_start: u8,
+    page_end: u8,
+    segment_remap: bool,
+    reverse_com_dir: bool,
+    multiplex: u8,
+    start_line: u8,
+    display_offset: u8,
+    invert: bool,
+    all_on: bool,
+    display_on: bool,
+    width: u8,
+    height: u8,
+    offset_x: u8,
+    offset_y: u8,
+    stats: BusStats,
+}
+
+/// Bus traffic recorded by an [`Emulator`].
+///
+/// Every call to `send_commands` or `send_data` counts as one transaction. On I2C each command
+/// transaction costs the device address and a control byte on top of its payload, so combining
+/// commands into fewer transactions saves two bytes per transaction avoided, plus the start and
+/// stop conditions.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct BusStats {
+    /// Number of command transactions.
+    pub command_transactions: usize,
+    /// Number of command bytes.
+    pub command_bytes: usize,
+    /// Number of data transactions.
+    pub data_transactions: usize,
+    /// Number of data bytes.
+    pub data_bytes: usize,
+}
+
+impl BusStats {
+    /// Number of bytes the recorded command transactions occupy on an I2C bus, including the
+    /// address and control byte of each transaction.
+    pub fn i2c_command_bytes(&self) -> usize {
+        self.command_bytes + 2 * self.command_transactions
+    }
+}
+
+impl Default for Emulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Emulator {
+    /// Create an emulator for a full size 128x64 panel.
+    ///
+    /// All controller registers are set to their reset values and the GDDRAM is cleared.
+    pub fn new() -> Self {
+        Self::with_panel(RAM_COLS, RAM_ROWS, 0, 0)
+    }
+
+    /// Create an emulator for a panel of `width` x `height` pixels, connected to the controller
+    /// starting at segment `offset_x` and common `offset_y`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the panel does not fit into the 128x64 GDDRAM.
+    pub fn with_panel(width: u8, height: u8, offset_x: u8, offset_y: u8) -> Self {
+        Self::with_driver::<DisplaySize128x64>(width, height, offset_x, offset_y)
+    }
+
+    /// Create an emulator for a panel connected to the display driver described by `SIZE`.
+    fn with_driver<SIZE: DisplaySize>(width: u8, height: u8, offset_x: u8, offset_y: u8) -> Self {
+        let (ram_cols, ram_rows) = (SIZE::DRIVER_COLS, SIZE::DRIVER_ROWS);
+        let transposed = SIZE::Controller::TRANSPOSED_RAM;
+
+        assert!(
+            ram_cols <= MAX_RAM_COLS && ram_rows <= MAX_RAM_PAGES * 8 && ram_rows % 8 == 0,
+            "Driver RAM size is not supported by the emulator"
+        );
+        assert!(
+            width > 0 && u16::from(width) + u16::from(offset_x) <= u16::from(ram_cols),
+            "Panel does not fit into the controller columns"
+        );
+        assert!(
+            height > 0 && u16::from(height) + u16::from(offset_y) <= u16::from(ram_rows),
+            "Panel does not fit into the controller rows"
+        );
+
+        let ram_pages = ram_rows / 8;
+
+        Self {
+            gddram: [0; MAX_RAM_COLS as usize * MAX_RAM_PAGES as usize],
+            ram_cols,
+            ram_pages,
+            page_addressing_only: SIZE::Controller::PAGE_ADDRESSING_ONLY,
+            charge_pump: SIZE::Controller::CHARGE_PUMP,
+            transposed,
+            addr_mode: AddrMode::Page,
+            column: 0,
+            page: 0,
+            column_start: 0,
+            column_end: ram_cols - 1,
+            page_start: 0,
+            page_end: ram_pages - 1,
+            segment_remap: false,
+            reverse_com_dir: false,
+            multiplex: if transposed { ram_cols } else { ram_rows } - 1,
+            start_line: 0,
+            display_offset: 0,
+            invert: false,
+            all_on: false,
+            display_on: false,
+            width,
+            height,
+            offset_x,
+            offset_y,
+            stats: BusStats::default(),
+        }
+    }
+
+    /// Create an emulator for the panel described by a [`DisplaySize`].
+    ///
+    /// If the controller has [`TRANSPOSED_RAM`](Controller::TRANSPOSED_RAM) set, the panel
+    /// columns are connected to the commons in the middle of the driver outputs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the panel does not fit into the driver RAM, or the driver RAM is larger than the
+    /// 132x128 pixels supported by the emulator.
+    pub fn for_size<SIZE: DisplaySize>(size: SIZE) -> Self {
+        let offset_x = if SIZE::Controller::TRANSPOSED_RAM {
+            (SIZE::DRIVER_COLS - size.width()) / 2
+        } else {
+            size.offset_x()
+        };
+
+        Self::with_driver::<SIZE>(size.width(), size.height(), offset_x, size.offset_y())
+    }
+
+    /// Panel width in pixels.
+    pub fn width(&self) -> u8 {
+        self.width
+    }
+
+    /// Panel height in pixels.
+    pub fn height(&self) -> u8 {
+        self.height
+    }
+
+    /// Raw GDDRAM contents, one byte per column and page, page by page.
+    pub fn gddram(&self) -> &[u8] {
+        &self.gddram[..usize::from(self.ram_cols) * usize::from(self.ram_pages)]
+    }
+
+    /// Bus traffic recorded since the emulator was created or [`reset_stats`](Self::reset_stats)
+    /// was last called.
+    ///
+    /// ```rust
+    /// use ssd1306::{emulator::Emulator, prelude::*, Ssd1306};
+    ///
+    /// let display = Ssd1306::new(Emulator::new(), DisplaySize128x64, DisplayRotation::Rotate0)
+    ///     .init()
+    ///     .unwrap();
+    ///
+    /// // The 17 initialisation commands are combined into 4 transactions
+    /// let stats = display.release().stats();
+    /// assert_eq!(stats.command_bytes, 26);
+    /// assert_eq!(stats.command_transactions, 4);
+    /// assert_eq!(stats.i2c_command_bytes(), 34);
+    /// ```
+    pub fn stats(&self) -> BusStats {
+        self.stats
+    }
+
+    /// Reset the recorded bus traffic.
+    pub fn reset_stats(&mut self) {
+        self.stats = BusStats::default();
+    }
+
+    /// Whether the display has been turned on.
+    pub fn is_display_on(&self) -> bool {
+        self.display_on
+    }
+
+    /// Get the state of a pixel as it would be visible on the panel.
+    ///
+    /// This takes the segment remap, COM scan direction, multiplex ratio, start line, display
+    /// offset, inversion, entire display on and display on/off settings into account. Pixels
+    /// outside the panel are reported as off.
+    pub fn pixel(&self, x: u8, y: u8) -> bool {
+        if x >= self.width || y >= self.height || !self.display_on {
+            return false;
+        }
+
+        if self.all_on {
+            return true;
+        }
+
+        // The panel is mounted so that its first column is wired to the last output of its range,
+        // and its first row to the last output of its range.
+        let (seg, com) = if self.transposed {
+            (
+                self.ram_rows() - 1 - self.offset_y - y,
+                self.offset_x + (self.width - 1 - x),
+            )
+        } else {
+            (
+                self.ram_cols - 1 - self.offset_x - x,
+                self.offset_y + (self.height - 1 - y),
+            )
+        };
+
+        let seg_line = if self.segment_remap {
+            self.segments() - 1 - seg
+        } else {
+            seg
+        };
+
+        // Commons outside of the multiplex ratio are not driven
+        let mux = self.multiplex + 1;
+        let first_com = if self.transposed {
+            (self.commons() - mux) / 2
+        } else {
+            0
+        };
+        if com < first_com || com >= first_com + mux {
+            return false;
+        }
+        let counter = if self.reverse_com_dir {
+            mux - 1 - (com - first_com)
+        } else {
+            com - first_com
+        };
+        let com_line = ((u16::from(first_com)
+            + u16::from(counter)
+            + u16::from(self.display_offset)
+            + u16::from(self.start_line))
+            % u16::from(self.commons())) as u8;
+
+        let (row, column) = if self.transposed {
+            (seg_line, com_line)
+        } else {
+            (com_line, seg_line)
+        };
+
+        let byte =
+            self.gddram[usize::from(row / 8) * usize::from(self.ram_cols) + usize::from(column)];
+
+        (byte & (1 << (row % 8)) != 0) ^ self.invert
+    }
+
+    fn ram_rows(&self) -> u8 {
+        self.ram_pages * 8
+    }
+
+    /// Number of segment outputs.
+    fn segments(&self) -> u8 {
+        if self.transposed {
+            self.ram_rows()
+        } else {
+            self.ram_cols
+        }
+    }
+
+    /// Number of common outputs.
+    fn commons(&self) -> u8 {
+        if self.transposed {
+            self.ram_cols
+        } else {
+            self.ram_rows()
+        }
+    }
+
+    /// Apply a buffer of command bytes.
+    fn commands(&mut self, bytes: &[u8]) -> Result<(), DisplayError> {
+        for command in Command::decode(bytes) {
+            self.command(command.map_err(|_| DisplayError::InvalidFormatError)?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply a single decoded command.
+    fn command(&mut self, command: Command) -> Result<(), DisplayError> {
+        if self.page_addressing_only {
+            if let Command::AddressMode(_)
+            | Command::ColumnAddress(..)
+            | Command::PageAddress(..)
+            | Command::HScrollSetup(..)
+            | Command::VHScrollSetup(..)
+            | Command::EnableScroll(_)
+            | Command::VScrollArea(..) = command
+            {
+                return Err(DisplayError::InvalidFormatError);
+            }
+        }
+        if !self.charge_pump {
+            if let Command::ChargePump(_) = command {
+                return Err(DisplayError::InvalidFormatError);
+            }
+        }
+        if self.transposed {
+            if let Command::StartLine(_) | Command::ComPinConfig(..) = command {
+                return Err(DisplayError::InvalidFormatError);
+            }
+        } else if let Command::DisplayStartLine(_) = command {
+            return Err(DisplayError::InvalidFormatError);
+        }
+
+        match command {
+            Command::LowerColStart(addr) => self.column = (self.column & 0xF0) | (addr & 0xF),
+            Command::UpperColStart(addr) => {
+                self.column = (self.column & 0x0F) | ((addr & 0xF) << 4)
+            }
+            Command::ColStart(addr) => {
+                if addr >= self.ram_cols {
+                    return Err(DisplayError::OutOfBoundsError);
+                }
+                self.column = addr;
+            }
+            Command::AddressMode(mode) => self.addr_mode = mode,
+            Command::ColumnAddress(start, end) => {
+                if start > end || end >= self.ram_cols {
+                    return Err(DisplayError::OutOfBoundsError);
+                }
+                self.column_start = start;
+                self.column_end = end;
+                self.column = start;
+            }
+            Command::PageAddress(start, end) => {
+                let (start, end) = (start as u8, end as u8);
+                if start > end || end >= self.ram_pages {
+                    return Err(DisplayError::OutOfBoundsError);
+                }
+                self.page_start = start;
+                self.page_end = end;
+                self.page = start;
+            }
+            Command::PageStart(page) => {
+                if page as u8 >= self.ram_pages {
+                    return Err(DisplayError::OutOfBoundsError);
+                }
+                self.page = page as u8;
+            }
+            Command::StartLine(line) => self.start_line = line & 0x3F,
+            Command::DisplayStartLine(line) => self.start_line = line % self.commons(),
+            Command::SegmentRemap(remap) => self.segment_remap = remap,
+            Command::ReverseComDir(rev) => self.reverse_com_dir = rev,
+            Command::Multiplex(ratio) => {
+                if !(15..self.commons()).contains(&ratio) {
+                    return Err(DisplayError::OutOfBoundsError);
+                }
+                self.multiplex = ratio;
+            }
+            Command::DisplayOffset(offset) => self.display_offset = offset % self.commons(),
+            Command::Invert(invert) => self.invert = invert,
+            Command::AllOn(on) => self.all_on = on,
+            Command::DisplayOn(on) => self.display_on = on,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Store a byte at the current address pointer and advance the pointer.
+    fn write(&mut self, byte: u8) {
+        // The column nibbles can be set to an address past the end of the RAM
+        if self.column < self.ram_cols {
+            let idx =
+                usize::from(self.page) * usize::from(self.ram_cols) + usize::from(self.column);
+            self.gddram[idx] = byte;
+        }
+
+        match self.addr_mode {
+            AddrMode::Page => {
+                self.column = if self.column >= self.ram_cols - 1 {
+                    0
+                } else {
+                    self.column + 1
+                };
+            }
+            AddrMode::Horizontal => {
+                if self.column >= self.column_end {
+                    self.column = self.column_start;
+                    self.page = if self.page >= self.page_end {
+                        self.page_start
+                    } else {
+                        self.page + 1
+                    };
+                } else {
+                    self.column += 1;
+                }
+            }
+            AddrMode::Vertical => {
+                if self.page >= self.page_end {
+                    self.page = self.page_start;
+                    self.column = if self.column >= self.column_end {
+                        self.column_start
+                    } else {
+                        self.column + 1
+                    };
+                } else {
+                    self.page += 1;
+                }
+            }
+        }
+    }
+
+    fn send_commands_impl(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        match cmd {
+            DataFormat::U8(bytes) => {
+                self.stats.command_transactions += 1;
+                self.stats.command_bytes += bytes.len();
+                self.commands(bytes)
+            }
+            _ => Err(DisplayError::DataFormatNotImplemented),
+        }
+    }
+
+    fn send_data_impl(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        let len = match buf {
+            DataFormat::U8(bytes) => {
+                bytes.iter().for_each(|byte| self.write(*byte));
+                bytes.len()
+            }
+            DataFormat::U8Iter(iter) => iter.map(|byte| self.write(byte)).count(),
+            _ => return Err(DisplayError::DataFormatNotImplemented),
+        };
+
+        self.stats.data_transactions += 1;
+        self.stats.data_bytes += len;
+
+        Ok(())
+    }
+}
+
+impl WriteOnlyDataCommand for Emulator {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.send_commands_impl(cmd)
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.send_data_impl(buf)
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncWriteOnlyDataCommand for Emulator {
+    async fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.send_commands_impl(cmd)
+    }
+
+    async fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.send_data_impl(buf)
+    }
+}
@@ -0,0 +1,251 @@
+//! Display initialisation configuration
+
+use crate::{brightness::Brightness, command::VcomhLevel};
+
+/// Controller settings sent to the display during initialisation.
+///
+/// The default configuration is suitable for the common SSD1306 modules which generate the panel
+/// voltage with the internal charge pump. Displays powered from an external VCC can disable the
+/// charge pump and adjust the timing and voltage settings as required by the panel datasheet.
+/// [`InitConfig::ssd1309`] provides the configuration for SSD1309 displays.
+///
+/// ```rust
+/// # use ssd1306::test_helpers::StubInterface;
+/// # let interface = StubInterface;
+/// use ssd1306::{command::VcomhLevel, prelude::*, Ssd1306};
+///
+/// let config = InitConfig::new()
+///     .charge_pump(false)
+///     .precharge(0x2, 0x2)
+///     .vcomh(VcomhLevel::V077);
+///
+/// let display = Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0)
+///     .into_buffered_graphics_mode()
+///     .init_with_config(config)
+///     .unwrap();
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InitConfig {
+    pub(crate) charge_pump: Option<bool>,
+    pub(crate) oscillator_frequency: u8,
+    pub(crate) divide_ratio: u8,
+    pub(crate) precharge_phase1: u8,
+    pub(crate) precharge_phase2: u8,
+    pub(crate) vcomh: Option<VcomhLevel>,
+    pub(crate) display_offset: u8,
+    pub(crate) start_line: u8,
+    pub(crate) com_pins: Option<(bool, bool)>,
+    pub(crate) brightness: Option<Brightness>,
+    pub(crate) invert: bool,
+    pub(crate) display_on: bool,
+}
+
+impl Default for InitConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InitConfig {
+    /// Create the default configuration.
+    pub const fn new() -> Self {
+        Self {
+            charge_pump: Some(true),
+            oscillator_frequency: 0x8,
+            divide_ratio: 0x0,
+            precharge_phase1: 0x1,
+            precharge_phase2: 0x2,
+            vcomh: Some(VcomhLevel::Auto),
+            display_offset: 0,
+            start_line: 0,
+            com_pins: None,
+            brightness: None,
+            invert: false,
+            display_on: true,
+        }
+    }
+
+    /// Create the configuration for SSD1309 displays.
+    ///
+    /// The SSD1309 is command compatible with the SSD1306, but has no internal charge pump and
+    /// needs an external VCC, so the charge pump command is not sent. The display clock and
+    /// precharge periods are set to the SSD1309 reset values, and the VCOMH level is left at its
+    /// reset value because the SSD1309 uses a different encoding.
+    ///
+    /// The SSD1309 must be reset before it is initialised:
+    ///
+    /// ```rust
+    /// # use ssd1306::test_helpers::{DelayStub, PinStub, StubInterface};
+    /// # let interface = StubInterface;
+    /// # let mut reset = PinStub;
+    /// # let mut delay = DelayStub;
+    /// use ssd1306::{prelude::*, Ssd1306};
+    ///
+    /// let mut display = Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0)
+    ///     .into_buffered_graphics_mode();
+    /// display.reset(&mut reset, &mut delay).unwrap();
+    /// let display = display.init_with_config(InitConfig::ssd1309()).unwrap();
+    /// ```
+    pub const fn ssd1309() -> Self {
+        Self {
+            charge_pump: None,
+            oscillator_frequency: 0x7,
+            divide_ratio: 0x0,
+            precharge_phase1: 0x2,
+            precharge_phase2: 0x2,
+            vcomh: None,
+            ..Self::new()
+        }
+    }
+
+    /// Enable or disable the internal charge pump. Default = enabled.
+    ///
+    /// The charge pump must be disabled if the panel is powered from an external VCC.
+    pub const fn charge_pump(self, enable: bool) -> Self {
+        Self {
+            charge_pump: Some(enable),
+            ..self
+        }
+    }
+
+    /// Set the oscillator frequency and the display clock divide ratio. Default = `(0x8, 0x0)`.
+    ///
+    /// Both values must be between 0 and 15. Higher `frequency` values increase the oscillator
+    /// frequency, the clock is divided by `divide_ratio + 1`. See [`Command::DisplayClockDiv`].
+    ///
+    /// [`Command::DisplayClockDiv`]: crate::command::Command::DisplayClockDiv
+    pub const fn clock(self, frequency: u8, divide_ratio: u8) -> Self {
+        debug_assert!(
+            frequency <= 15,
+            "Oscillator frequency must be between 0 and 15"
+        );
+        debug_assert!(divide_ratio <= 15, "Divide ratio must be between 0 and 15");
+
+        Self {
+            oscillator_frequency: frequency,
+            divide_ratio,
+            ..self
+        }
+    }
+
+    /// Set the length of the two precharge phases in display clocks. Default = `(1, 2)`.
+    ///
+    /// Both values must be between 1 and 15. Note that
+    /// [`set_brightness`](crate::Ssd1306::set_brightness) also sets phase 2, while phase 1 is kept.
+    pub const fn precharge(self, phase1: u8, phase2: u8) -> Self {
+        debug_assert!(
+            0 < phase1 && phase1 <= 15,
+            "Precharge phase 1 must be between 1 and 15"
+        );
+        debug_assert!(
+            0 < phase2 && phase2 <= 15,
+            "Precharge phase 2 must be between 1 and 15"
+        );
+
+        Self {
+            precharge_phase1: phase1,
+            precharge_phase2: phase2,
+            ..self
+        }
+    }
+
+    /// Set the VCOMH deselect level. Default = [`VcomhLevel::Auto`].
+    pub const fn vcomh(self, level: VcomhLevel) -> Self {
+        Self {
+            vcomh: Some(level),
+            ..self
+        }
+    }
+
+    /// Set the vertical display offset in rows. Default = 0.
+    ///
+    /// The offset ranges from 0 to the number of driver rows minus one, given by
+    /// [`DisplaySize::DRIVER_ROWS`](crate::size::DisplaySize::DRIVER_ROWS), e.g. 63 on the SSD1306
+    /// and 127 on the SH1107.
+    pub const fn display_offset(self, offset: u8) -> Self {
+        Self {
+            display_offset: offset,
+            ..self
+        }
+    }
+
+    /// Set the display RAM row shown at the top of the display. Default = 0.
+    ///
+    /// Like the [`display_offset`](Self::display_offset), the line ranges from 0 to
+    /// [`DisplaySize::DRIVER_ROWS`](crate::size::DisplaySize::DRIVER_ROWS) minus one.
+    pub const fn start_line(self, line: u8) -> Self {
+        Self {
+            start_line: line,
+            ..self
+        }
+    }
+
+    /// Override the COM pin hardware configuration of the display size.
+    ///
+    /// `alternative` selects the alternative (`true`) or sequential (`false`) COM pin
+    /// configuration, `lr_remap` enables the left/right remap. By default the configuration of the
+    /// [`DisplaySize`](crate::size::DisplaySize) is used. See [`Command::ComPinConfig`].
+    ///
+    /// [`Command::ComPinConfig`]: crate::command::Command::ComPinConfig
+    pub const fn com_pins(self, alternative: bool, lr_remap: bool) -> Self {
+        Self {
+            com_pins: Some((alternative, lr_remap)),
+            ..self
+        }
+    }
+
+    /// Get whether the internal charge pump is enabled, or `None` if the charge pump command is
+    /// not sent.
+    pub const fn get_charge_pump(&self) -> Option<bool> {
+        self.charge_pump
+    }
+
+    /// Get the oscillator frequency and the display clock divide ratio.
+    pub const fn get_clock(&self) -> (u8, u8) {
+        (self.oscillator_frequency, self.divide_ratio)
+    }
+
+    /// Get the length of the two precharge phases in display clocks.
+    ///
+    /// Phase 2 is replaced by the brightness, if one is set.
+    pub const fn get_precharge(&self) -> (u8, u8) {
+        (self.precharge_phase1, self.precharge_phase2)
+    }
+
+    /// Get the VCOMH deselect level, or `None` if the VCOMH command is not sent.
+    pub const fn get_vcomh(&self) -> Option<VcomhLevel> {
+        self.vcomh
+    }
+
+    /// Get the vertical display offset in rows.
+    pub const fn get_display_offset(&self) -> u8 {
+        self.display_offset
+    }
+
+    /// Get the display RAM row shown at the top of the display.
+    pub const fn get_start_line(&self) -> u8 {
+        self.start_line
+    }
+
+    /// Get the COM pin hardware configuration as `(alternative, lr_remap)`, or `None` if the
+    /// configuration of the [`DisplaySize`](crate::size::DisplaySize) is used.
+    pub const fn get_com_pins(&self) -> Option<(bool, bool)> {
+        self.com_pins
+    }
+
+    /// Get the display brightness, or `None` for the precharge periods of the configuration and
+    /// the default contrast.
+    pub const fn get_brightness(&self) -> Option<Brightness> {
+        self.brightness
+    }
+
+    /// Get whether the screen pixels are inverted.
+    pub const fn get_invert(&self) -> bool {
+        self.invert
+    }
+
+    /// Get whether the display is turned on at the end of the initialisation.
+    pub const fn get_display_on(&self) -> bool {
+        self.display_on
+    }
+}
@@ -39,8 +39,10 @@
 //!     interface,
 //!     DisplaySize128x64,
 //!     DisplayRotation::Rotate0,
-//! ).into_buffered_graphics_mode();
-//! display.init().unwrap();
+//! )
+//! .into_buffered_graphics_mode()
+//! .init()
+//! .unwrap();
 //!
 //! let text_style = MonoTextStyleBuilder::new()
 //!     .font(&FONT_6X10)
@@ -75,8 +77,10 @@
 //!     interface,
 //!     DisplaySize128x64,
 //!     DisplayRotation::Rotate0,
-//! ).into_terminal_mode();
-//! display.init().unwrap();
+//! )
+//! .into_terminal_mode()
+//! .init()
+//! .unwrap();
 //! display.clear().unwrap();
 //!
 //! // Spam some characters to the display
@@ -110,70 +114,107 @@
 #![allow(async_fn_in_trait)]
 
 mod brightness;
+mod builder;
 pub mod command;
+pub mod controller;
+#[cfg(feature = "emulator")]
+pub mod emulator;
 mod error;
 mod i2c_interface;
+mod init_config;
 pub mod mode;
 pub mod prelude;
 pub mod rotation;
 pub mod size;
+pub mod state;
 #[doc(hidden)]
 pub mod test_helpers;
 
-use core::convert::Infallible;
+/// Re-exports used by the [`display_size!`] macro.
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "async")]
+    pub use display_interface::AsyncWriteOnlyDataCommand;
+    pub use display_interface::{DisplayError, WriteOnlyDataCommand};
+}
+
+use core::{convert::Infallible, marker::PhantomData};
 
-pub use crate::i2c_interface::I2CDisplayInterface;
-use crate::mode::BasicMode;
+#[cfg(feature = "async")]
+pub use crate::builder::Ssd1306BuilderAsync;
+#[cfg(feature = "async")]
+use crate::mode::DisplayConfigAsync;
+use crate::mode::{BasicMode, DisplayConfig};
+pub use crate::{builder::Ssd1306Builder, i2c_interface::I2CDisplayInterface};
 use brightness::Brightness;
+use command::{AddrMode, Command, CommandBatch};
+#[cfg(feature = "async")]
+use command::{CommandAsync, CommandBatchAsync};
+use controller::Controller;
 #[cfg(feature = "async")]
-use command::CommandAsync;
-use command::{AddrMode, Command, VcomhLevel};
+use controller::ControllerAsync;
 #[cfg(feature = "async")]
 use display_interface::AsyncWriteOnlyDataCommand;
-use display_interface::{DataFormat::U8, DisplayError, WriteOnlyDataCommand};
+use display_interface::{DisplayError, WriteOnlyDataCommand};
 use embedded_hal::{delay::DelayNs, digital::OutputPin};
 #[cfg(feature = "async")]
 use embedded_hal_async::delay::DelayNs as DelayNsAsync;
 use error::Error;
+use init_config::InitConfig;
 use mode::{BufferedGraphicsMode, TerminalMode};
 #[cfg(feature = "async")]
 use mode::{BufferedGraphicsModeAsync, TerminalModeAsync};
-use rotation::DisplayRotation;
-use size::DisplaySize;
+use rotation::{DisplayOrientation, DisplayRotation};
 #[cfg(feature = "async")]
 use size::DisplaySizeAsync;
+use size::{DisplaySize, NewZeroed};
+use state::{Initialized, Uninitialized};
 
 /// SSD1306 driver.
 ///
-/// Note that some methods are only available when the display is configured in a certain [`mode`].
-#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async"))]
+/// Note that some methods are only available when the display is configured in a certain [`mode`],
+/// and most methods only once the display is [`Initialized`], see [`state`].
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(feature = "async", idents(DisplaySize(async = "DisplaySizeAsync")))
+)]
 #[derive(Copy, Clone, Debug)]
-pub struct Ssd1306<DI, SIZE, MODE> {
+pub struct Ssd1306<DI, SIZE, MODE, STATE = Initialized>
+where
+    SIZE: DisplaySize,
+{
     interface: DI,
     mode: MODE,
     size: SIZE,
+    controller: SIZE::Controller,
     addr_mode: AddrMode,
-    rotation: DisplayRotation,
+    orientation: DisplayOrientation,
+    init_config: InitConfig,
+    state: PhantomData<STATE>,
 }
 
 #[maybe_async_cfg::maybe(
     sync(keep_self,),
     async(feature = "async", idents(DisplaySize(async = "DisplaySizeAsync")))
 )]
-impl<DI, SIZE> Ssd1306<DI, SIZE, BasicMode>
+impl<DI, SIZE> Ssd1306<DI, SIZE, BasicMode, Uninitialized>
 where
     SIZE: DisplaySize,
 {
     /// Create a basic SSD1306 interface.
     ///
-    /// Use the `into_*_mode` methods to enable more functionality.
+    /// Use the `into_*_mode` methods to enable more functionality, then [`init`](Self::init) to
+    /// initialise the display.
     pub fn new(interface: DI, size: SIZE, rotation: DisplayRotation) -> Self {
         Self {
             interface,
             size,
             addr_mode: AddrMode::Page,
             mode: BasicMode,
-            rotation,
+            orientation: rotation.into(),
+            controller: Default::default(),
+            init_config: InitConfig::default(),
+            state: PhantomData,
         }
     }
 }
@@ -189,35 +230,158 @@ where
         )
     )
 )]
-impl<DI, SIZE, MODE> Ssd1306<DI, SIZE, MODE>
+impl<DI, SIZE, MODE, STATE> Ssd1306<DI, SIZE, MODE, STATE>
 where
     SIZE: DisplaySize,
 {
-    /// Convert the display into another interface mode.
-    fn into_mode<MODE2>(self, mode: MODE2) -> Ssd1306<DI, SIZE, MODE2> {
+    /// Convert the display into another interface mode, which must be initialised again.
+    fn into_mode<MODE2>(self, mode: MODE2) -> Ssd1306<DI, SIZE, MODE2, Uninitialized> {
         Ssd1306 {
             mode,
             addr_mode: self.addr_mode,
             interface: self.interface,
             size: self.size,
-            rotation: self.rotation,
+            controller: self.controller,
+            orientation: self.orientation,
+            init_config: self.init_config,
+            state: PhantomData,
+        }
+    }
+
+    /// Change the initialisation state of the display.
+    fn into_state<STATE2>(self) -> Ssd1306<DI, SIZE, MODE, STATE2> {
+        Ssd1306 {
+            mode: self.mode,
+            addr_mode: self.addr_mode,
+            interface: self.interface,
+            size: self.size,
+            controller: self.controller,
+            orientation: self.orientation,
+            init_config: self.init_config,
+            state: PhantomData,
         }
     }
 
     /// Convert the display into a buffered graphics mode, supporting
     /// [embedded-graphics](https://crates.io/crates/embedded-graphics).
     ///
-    /// See [`BufferedGraphicsMode`] for more information.
-    pub fn into_buffered_graphics_mode(self) -> Ssd1306<DI, SIZE, BufferedGraphicsMode<SIZE>> {
+    /// The display must be initialised again in the new mode. See [`BufferedGraphicsMode`] for
+    /// more information.
+    pub fn into_buffered_graphics_mode(
+        self,
+    ) -> Ssd1306<DI, SIZE, BufferedGraphicsMode<SIZE>, Uninitialized> {
         self.into_mode(BufferedGraphicsMode::new())
     }
 
+    /// Convert the display into a buffered graphics mode which keeps a copy of the display RAM,
+    /// so [`flush`](Ssd1306::flush) only sends the bytes which changed.
+    ///
+    /// The display must be initialised again in the new mode. See [`BufferedGraphicsMode`] for
+    /// more information.
+    pub fn into_buffered_graphics_mode_with_shadow(
+        self,
+    ) -> Ssd1306<DI, SIZE, BufferedGraphicsMode<SIZE, SIZE::Buffer>, Uninitialized> {
+        self.into_mode(BufferedGraphicsMode::with_shadow(NewZeroed::new_zeroed()))
+    }
+
     /// Convert the display into a text-only, terminal-like mode.
     ///
-    /// See [`TerminalMode`] for more information.
-    pub fn into_terminal_mode(self) -> Ssd1306<DI, SIZE, TerminalMode> {
+    /// The display must be initialised again in the new mode. See [`TerminalMode`] for more
+    /// information.
+    pub fn into_terminal_mode(self) -> Ssd1306<DI, SIZE, TerminalMode, Uninitialized> {
         self.into_mode(TerminalMode::new())
     }
+
+    /// Get display dimensions, taking into account the current rotation of the display
+    ///
+    /// ```rust
+    /// # use ssd1306::test_helpers::StubInterface;
+    /// # let interface = StubInterface;
+    /// use ssd1306::{mode::TerminalMode, prelude::*, Ssd1306};
+    ///
+    /// let mut display = Ssd1306::new(
+    ///     interface,
+    ///     DisplaySize128x64,
+    ///     DisplayRotation::Rotate0,
+    /// ).into_terminal_mode();
+    /// assert_eq!(display.dimensions(), (128, 64));
+    ///
+    /// # let interface = StubInterface;
+    /// let mut rotated_display = Ssd1306::new(
+    ///     interface,
+    ///     DisplaySize128x64,
+    ///     DisplayRotation::Rotate90,
+    /// ).into_terminal_mode();
+    /// assert_eq!(rotated_display.dimensions(), (64, 128));
+    /// ```
+    pub fn dimensions(&self) -> (u8, u8) {
+        match self.orientation.rotation() {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
+                (self.size.width(), self.size.height())
+            }
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
+                (self.size.height(), self.size.width())
+            }
+        }
+    }
+
+    /// Get the display rotation.
+    pub fn rotation(&self) -> DisplayRotation {
+        self.orientation.rotation()
+    }
+
+    /// Get the display orientation, which also includes whether the display is mirrored.
+    pub fn orientation(&self) -> DisplayOrientation {
+        self.orientation
+    }
+
+    /// Release the contained interface.
+    pub fn release(self) -> DI {
+        self.interface
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(
+        feature = "async",
+        idents(
+            DisplayConfig(async = "DisplayConfigAsync"),
+            DisplaySize(async = "DisplaySizeAsync"),
+        )
+    )
+)]
+impl<DI, SIZE, MODE> Ssd1306<DI, SIZE, MODE, Uninitialized>
+where
+    SIZE: DisplaySize,
+    Ssd1306<DI, SIZE, MODE>: DisplayConfig,
+{
+    /// Initialise and configure the display for its mode.
+    ///
+    /// Consumes the uninitialised display and returns the initialised display, which can be
+    /// drawn to.
+    #[allow(clippy::type_complexity)]
+    pub async fn init(
+        self,
+    ) -> Result<Ssd1306<DI, SIZE, MODE>, <Ssd1306<DI, SIZE, MODE> as DisplayConfig>::Error> {
+        let mut display: Ssd1306<DI, SIZE, MODE> = self.into_state();
+        display.init().await?;
+        Ok(display)
+    }
+
+    /// Initialise and configure the display for its mode, using custom controller settings.
+    ///
+    /// The configuration is kept and used again when the display is initialised again with
+    /// [`DisplayConfig::init`].
+    #[allow(clippy::type_complexity)]
+    pub async fn init_with_config(
+        self,
+        config: InitConfig,
+    ) -> Result<Ssd1306<DI, SIZE, MODE>, <Ssd1306<DI, SIZE, MODE> as DisplayConfig>::Error> {
+        let mut display: Ssd1306<DI, SIZE, MODE> = self.into_state();
+        display.init_with_config(config).await?;
+        Ok(display)
+    }
 }
 
 #[maybe_async_cfg::maybe(
@@ -226,6 +390,7 @@ where
         feature = "async",
         idents(
             Command(async = "CommandAsync"),
+            CommandBatch(async = "CommandBatchAsync"),
             DisplaySize(async = "DisplaySizeAsync"),
             WriteOnlyDataCommand(async = "AsyncWriteOnlyDataCommand"),
         )
@@ -237,44 +402,31 @@ where
     SIZE: DisplaySize,
 {
     /// Initialise the display in one of the available addressing modes.
+    ///
+    /// All initialisation commands are sent to the display in a single transaction.
     pub async fn init_with_addr_mode(&mut self, mode: AddrMode) -> Result<(), DisplayError> {
-        let rotation = self.rotation;
-
-        Command::DisplayOn(false).send(&mut self.interface).await?;
-        Command::DisplayClockDiv(0x8, 0x0)
-            .send(&mut self.interface)
-            .await?;
-        Command::Multiplex(SIZE::HEIGHT - 1)
-            .send(&mut self.interface)
+        self.controller
+            .init(
+                &mut self.interface,
+                &self.size,
+                &self.init_config,
+                self.orientation,
+                mode,
+            )
             .await?;
-        Command::DisplayOffset(0).send(&mut self.interface).await?;
-        Command::StartLine(0).send(&mut self.interface).await?;
-        // TODO: Ability to turn charge pump on/off
-        Command::ChargePump(true).send(&mut self.interface).await?;
-        Command::AddressMode(mode).send(&mut self.interface).await?;
-
-        self.size.configure(&mut self.interface).await?;
-        self.set_rotation(rotation).await?;
-
-        self.set_brightness(Brightness::default()).await?;
-        Command::VcomhDeselect(VcomhLevel::Auto)
-            .send(&mut self.interface)
-            .await?;
-        Command::AllOn(false).send(&mut self.interface).await?;
-        Command::Invert(false).send(&mut self.interface).await?;
-        Command::EnableScroll(false)
-            .send(&mut self.interface)
-            .await?;
-        Command::DisplayOn(true).send(&mut self.interface).await?;
-
         self.addr_mode = mode;
 
         Ok(())
     }
 
     /// Change the addressing mode
+    ///
+    /// If the display driver only supports page addressing, horizontal addressing is emulated and
+    /// vertical addressing is not supported.
     pub async fn set_addr_mode(&mut self, mode: AddrMode) -> Result<(), DisplayError> {
-        Command::AddressMode(mode).send(&mut self.interface).await?;
+        self.controller
+            .set_addr_mode::<SIZE>(&mut self.interface, mode)
+            .await?;
         self.addr_mode = mode;
         Ok(())
     }
@@ -293,6 +445,7 @@ where
     ) -> Result<(), DisplayError> {
         Self::flush_buffer_chunks(
             &mut self.interface,
+            &mut self.controller,
             buffer,
             disp_width,
             upper_left,
@@ -303,163 +456,81 @@ where
 
     /// Send a raw buffer to the display.
     pub async fn draw(&mut self, buffer: &[u8]) -> Result<(), DisplayError> {
-        self.interface.send_data(U8(buffer)).await
+        self.controller
+            .write_data(&mut self.interface, buffer)
+            .await
     }
 
-    /// Get display dimensions, taking into account the current rotation of the display
-    ///
-    /// ```rust
-    /// # use ssd1306::test_helpers::StubInterface;
-    /// # let interface = StubInterface;
-    /// use ssd1306::{mode::TerminalMode, prelude::*, Ssd1306};
-    ///
-    /// let mut display = Ssd1306::new(
-    ///     interface,
-    ///     DisplaySize128x64,
-    ///     DisplayRotation::Rotate0,
-    /// ).into_terminal_mode();
-    /// assert_eq!(display.dimensions(), (128, 64));
-    ///
-    /// # let interface = StubInterface;
-    /// let mut rotated_display = Ssd1306::new(
-    ///     interface,
-    ///     DisplaySize128x64,
-    ///     DisplayRotation::Rotate90,
-    /// ).into_terminal_mode();
-    /// assert_eq!(rotated_display.dimensions(), (64, 128));
-    /// ```
-    pub fn dimensions(&self) -> (u8, u8) {
-        match self.rotation {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (SIZE::WIDTH, SIZE::HEIGHT),
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (SIZE::HEIGHT, SIZE::WIDTH),
-        }
-    }
+    /// Set the display orientation.
+    pub async fn set_orientation(
+        &mut self,
+        orientation: DisplayOrientation,
+    ) -> Result<(), DisplayError> {
+        self.orientation = orientation;
 
-    /// Get the display rotation.
-    pub fn rotation(&self) -> DisplayRotation {
-        self.rotation
+        let mut batch = CommandBatch::new(&mut self.interface);
+        self.controller
+            .set_orientation(&mut batch, orientation)
+            .await?;
+        batch.flush().await
     }
 
-    /// Set the display rotation.
+    /// Set the display rotation. The display stays mirrored if it was mirrored before.
     pub async fn set_rotation(&mut self, rotation: DisplayRotation) -> Result<(), DisplayError> {
-        self.rotation = rotation;
-
-        match rotation {
-            DisplayRotation::Rotate0 => {
-                Command::SegmentRemap(true)
-                    .send(&mut self.interface)
-                    .await?;
-                Command::ReverseComDir(true)
-                    .send(&mut self.interface)
-                    .await?;
-            }
-            DisplayRotation::Rotate90 => {
-                Command::SegmentRemap(false)
-                    .send(&mut self.interface)
-                    .await?;
-                Command::ReverseComDir(true)
-                    .send(&mut self.interface)
-                    .await?;
-            }
-            DisplayRotation::Rotate180 => {
-                Command::SegmentRemap(false)
-                    .send(&mut self.interface)
-                    .await?;
-                Command::ReverseComDir(false)
-                    .send(&mut self.interface)
-                    .await?;
-            }
-            DisplayRotation::Rotate270 => {
-                Command::SegmentRemap(true)
-                    .send(&mut self.interface)
-                    .await?;
-                Command::ReverseComDir(false)
-                    .send(&mut self.interface)
-                    .await?;
-            }
-        };
-
-        Ok(())
+        self.set_orientation(DisplayOrientation::new(
+            rotation,
+            self.orientation.is_mirrored(),
+        ))
+        .await
     }
 
-    /// Set mirror enabled/disabled.
+    /// Set mirror enabled/disabled. The display is mirrored left to right in its current rotation.
     pub async fn set_mirror(&mut self, mirror: bool) -> Result<(), DisplayError> {
-        if mirror {
-            match self.rotation {
-                DisplayRotation::Rotate0 => {
-                    Command::SegmentRemap(false)
-                        .send(&mut self.interface)
-                        .await?;
-                    Command::ReverseComDir(true)
-                        .send(&mut self.interface)
-                        .await?;
-                }
-                DisplayRotation::Rotate90 => {
-                    Command::SegmentRemap(false)
-                        .send(&mut self.interface)
-                        .await?;
-                    Command::ReverseComDir(false)
-                        .send(&mut self.interface)
-                        .await?;
-                }
-                DisplayRotation::Rotate180 => {
-                    Command::SegmentRemap(true)
-                        .send(&mut self.interface)
-                        .await?;
-                    Command::ReverseComDir(false)
-                        .send(&mut self.interface)
-                        .await?;
-                }
-                DisplayRotation::Rotate270 => {
-                    Command::SegmentRemap(true)
-                        .send(&mut self.interface)
-                        .await?;
-                    Command::ReverseComDir(true)
-                        .send(&mut self.interface)
-                        .await?;
-                }
-            };
-        } else {
-            self.set_rotation(self.rotation).await?;
-        }
-        Ok(())
+        self.set_orientation(DisplayOrientation::new(self.orientation.rotation(), mirror))
+            .await
     }
 
     /// Change the display brightness.
+    ///
+    /// The precharge phase 1 length of the [`InitConfig`] is kept. The brightness is stored in the
+    /// [`InitConfig`], so it is kept when the display is initialised again.
     pub async fn set_brightness(&mut self, brightness: Brightness) -> Result<(), DisplayError> {
-        Command::PreChargePeriod(1, brightness.precharge)
-            .send(&mut self.interface)
+        self.init_config.brightness = Some(brightness);
+
+        let mut batch = CommandBatch::new(&mut self.interface);
+        Command::PreChargePeriod(self.init_config.precharge_phase1, brightness.precharge)
+            .send(&mut batch)
             .await?;
         Command::Contrast(brightness.contrast)
-            .send(&mut self.interface)
-            .await
+            .send(&mut batch)
+            .await?;
+        batch.flush().await
     }
 
     /// Turn the display on or off. The display can be drawn to and retains all
     /// of its memory even while off.
+    ///
+    /// The setting is stored in the [`InitConfig`], so it is kept when the display is initialised
+    /// again.
     pub async fn set_display_on(&mut self, on: bool) -> Result<(), DisplayError> {
+        self.init_config.display_on = on;
         Command::DisplayOn(on).send(&mut self.interface).await
     }
 
     /// Set the position in the framebuffer of the display limiting where any sent data should be
     /// drawn. This method can be used for changing the affected area on the screen as well
     /// as (re-)setting the start point of the next `draw` call.
+    ///
+    /// If the display driver only supports page addressing, the area is only taken into account
+    /// in horizontal addressing mode, otherwise just the start point is set.
     pub async fn set_draw_area(
         &mut self,
         start: (u8, u8),
         end: (u8, u8),
     ) -> Result<(), DisplayError> {
-        Command::ColumnAddress(start.0, end.0.saturating_sub(1))
-            .send(&mut self.interface)
-            .await?;
-
-        if self.addr_mode != AddrMode::Page {
-            Command::PageAddress(start.1.into(), (end.1.saturating_sub(1)).into())
-                .send(&mut self.interface)
-                .await?;
-        }
-
-        Ok(())
+        self.controller
+            .set_draw_area(&mut self.interface, self.addr_mode, start, end)
+            .await
     }
 
     /// Set the column address in the framebuffer of the display where any sent data should be
@@ -480,12 +551,31 @@ where
     }
 
     /// Set the screen pixel on/off inversion
+    ///
+    /// The setting is stored in the [`InitConfig`], so it is kept when the display is initialised
+    /// again.
     pub async fn set_invert(&mut self, invert: bool) -> Result<(), DisplayError> {
+        self.init_config.invert = invert;
         Command::Invert(invert).send(&mut self.interface).await
     }
 
+    /// Offset of the first display column in the driver RAM, taking into account the current
+    /// orientation of the display.
+    fn column_offset(&self) -> u8 {
+        let (remap, _) = self.orientation.scan_direction();
+
+        if !remap && !SIZE::Controller::TRANSPOSED_RAM {
+            // If segment remapping is flipped, we need to calculate
+            // the offset from the other edge of the display.
+            SIZE::DRIVER_COLS - self.size.width() - self.size.offset_x()
+        } else {
+            self.size.offset_x()
+        }
+    }
+
     async fn flush_buffer_chunks(
         interface: &mut DI,
+        controller: &mut SIZE::Controller,
         buffer: &[u8],
         disp_width: usize,
         upper_left: (u8, u8),
@@ -508,15 +598,10 @@ where
             .take(num_pages)
             .map(|s| &s[page_lower..page_upper])
         {
-            interface.send_data(U8(c)).await?
+            controller.write_data(interface, c).await?
         }
         Ok(())
     }
-
-    /// Release the contained interface.
-    pub fn release(self) -> DI {
-        self.interface
-    }
 }
 
 // SPI-only reset
@@ -532,8 +617,14 @@ where
         )
     )
 )]
-impl<DI, SIZE, MODE> Ssd1306<DI, SIZE, MODE> {
+impl<DI, SIZE, MODE, STATE> Ssd1306<DI, SIZE, MODE, STATE>
+where
+    SIZE: DisplaySize,
+{
     /// Reset the display.
+    ///
+    /// Displays like the SSD1309 must be reset with this method before they are initialised, see
+    /// [`InitConfig::ssd1309`].
     pub async fn reset<RST, DELAY>(
         &mut self,
         rst: &mut RST,
@@ -2,7 +2,8 @@
 
 use crate::{
     command::AddrMode,
-    rotation::DisplayRotation,
+    init_config::InitConfig,
+    rotation::{DisplayOrientation, DisplayRotation},
     size::{DisplaySize, NewZeroed},
     Ssd1306,
 };
@@ -12,26 +13,195 @@ use crate::{size::DisplaySizeAsync, Ssd1306Async};
 use display_interface::AsyncWriteOnlyDataCommand;
 use display_interface::{DisplayError, WriteOnlyDataCommand};
 
+/// Maximum number of pages in the display RAM, for drivers with up to 128 rows.
+const MAX_PAGES: usize = 16;
+
+/// Column span of a page without changes.
+const CLEAN: (u8, u8) = (u8::MAX, 0);
+
+/// Approximate number of bytes sent to set the draw area of a region in [`flush`], used to
+/// decide whether it is cheaper to merge two regions.
+///
+/// [`flush`]: Ssd1306::flush
+const REGION_COST: usize = 8;
+
 /// Buffered graphics mode.
 ///
 /// This mode keeps a pixel buffer in system memory, up to 1024 bytes for 128x64px displays. This
 /// buffer is drawn to by [`set_pixel`](Ssd1306::set_pixel) commands or
 /// [`embedded-graphics`](https://docs.rs/embedded-graphics) commands. The display can then be
 /// updated using the [`flush`](Ssd1306::flush) method.
+///
+/// The buffer is laid out like the display RAM, so it depends on the orientation of the display.
+/// When the orientation is changed, e.g. with [`set_rotation`](Ssd1306::set_rotation), the whole
+/// display is marked as changed and updated by the next flush:
+///
+/// - Rotating by 90° or 270° swaps the axes of the display, so the buffer is cleared.
+/// - Rotating by 180° or changing the mirroring keeps the content of the buffer, which is then
+///   shown in the new orientation.
+///
+/// Panels whose mounting cannot be corrected with the segment remap and COM scan direction of the
+/// display, e.g. because they are fixed by the board wiring, can additionally be rotated and
+/// mirrored in software with [`set_software_orientation`](Ssd1306::set_software_orientation).
+///
+/// By default the mode has [`NoShadow`] buffer and [`flush`](Ssd1306::flush) sends every part of
+/// the buffer that was drawn to. A display converted with
+/// [`into_buffered_graphics_mode_with_shadow`](Ssd1306::into_buffered_graphics_mode_with_shadow)
+/// keeps a second buffer with a copy of the display RAM instead, and only sends the bytes which
+/// actually changed. This doubles the memory used by the mode, but saves bus bandwidth when the
+/// same pixels are drawn again, e.g. by user interfaces which redraw everything for each frame.
 #[maybe_async_cfg::maybe(
     sync(keep_self),
     async(feature = "async", idents(DisplaySize(async = "DisplaySizeAsync")))
 )]
 #[derive(Clone, Debug)]
-pub struct BufferedGraphicsMode<SIZE>
+pub struct BufferedGraphicsMode<SIZE, SHADOW = NoShadow>
 where
     SIZE: DisplaySize,
 {
     buffer: SIZE::Buffer,
-    min_x: u8,
-    max_x: u8,
-    min_y: u8,
-    max_y: u8,
+    /// Changed columns of each page of the buffer
+    dirty: [(u8, u8); MAX_PAGES],
+    /// Columns of each page which remain to be sent by the current `flush_step` frame
+    pending: [(u8, u8); MAX_PAGES],
+    orientation: DisplayOrientation,
+    software_orientation: DisplayOrientation,
+    /// Copy of the display RAM contents, in the layout of the buffer
+    shadow: SHADOW,
+    /// Whether the shadow buffer matches the display RAM
+    shadow_valid: bool,
+    /// Maximum number of unchanged bytes sent between two changed bytes
+    diff_gap: u8,
+}
+
+/// Progress of an incremental flush with [`flush_step`](Ssd1306::flush_step).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlushProgress {
+    /// Parts of the frame remain to be sent by further calls.
+    Pending,
+    /// The whole frame was sent to the display.
+    Complete,
+}
+
+impl FlushProgress {
+    /// Whether the whole frame was sent to the display.
+    pub fn is_complete(self) -> bool {
+        self == FlushProgress::Complete
+    }
+}
+
+/// Shadow buffer type of a [`BufferedGraphicsMode`] which does not keep a copy of the display RAM.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoShadow;
+
+impl AsMut<[u8]> for NoShadow {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut []
+    }
+}
+
+/// Frame buffer which is drawn to while a display in [`BufferedGraphicsMode`] is flushed.
+///
+/// A flush borrows the display until the changes are sent, so the next frame cannot be drawn to
+/// the display in the meantime. A frame buffer created with
+/// [`frame_buffer`](Ssd1306::frame_buffer) can be drawn to instead, and the finished frame is
+/// handed to the display with [`swap`](Ssd1306::swap). With the async driver, the next frame can
+/// then be drawn while the previous one is sent, e.g. by joining the future of the flush with the
+/// drawing code.
+///
+/// The frame buffer is drawn to like the display, including the
+/// [software orientation](FrameBuffer::set_software_orientation). It keeps track of the columns
+/// of each page which differ from the buffer of the display, so only these are sent by the flush
+/// after a [`swap`](Ssd1306::swap).
+///
+/// Swapping exchanges the buffers, so the frame buffer then holds the previous frame of the
+/// display, which is usually cleared before the next frame is drawn. While a frame buffer is in
+/// use, the display should not be drawn to directly, as the frame buffer does not know about these
+/// changes.
+///
+/// ```rust
+/// # use ssd1306::test_helpers::StubInterface;
+/// # let interface = StubInterface;
+/// use ssd1306::{prelude::*, Ssd1306};
+///
+/// let mut display = Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0)
+///     .into_buffered_graphics_mode()
+///     .init()
+///     .unwrap();
+/// let mut frame = display.frame_buffer();
+/// frame.set_pixel(0, 0, true);
+///
+/// for i in 1..4 {
+///     display.swap(&mut frame);
+///
+///     // Draw the next frame while the previous one is sent
+///     frame.clear_buffer();
+///     frame.set_pixel(i, i, true);
+///     while !display.flush_step(128).unwrap().is_complete() {}
+/// }
+/// ```
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(feature = "async", idents(DisplaySize(async = "DisplaySizeAsync")))
+)]
+#[derive(Debug)]
+pub struct FrameBuffer<SIZE>
+where
+    SIZE: DisplaySize,
+{
+    buffer: SIZE::Buffer,
+    /// Columns of each page which differ from the buffer of the display
+    dirty: [(u8, u8); MAX_PAGES],
+    /// Width and height of the buffer in the display RAM layout
+    size: (u8, u8),
+    orientation: DisplayOrientation,
+    software_orientation: DisplayOrientation,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(feature = "async", idents(DisplaySize(async = "DisplaySizeAsync")))
+)]
+impl<SIZE> FrameBuffer<SIZE>
+where
+    SIZE: DisplaySize,
+{
+    /// Turn a pixel on or off. A non-zero `value` is treated as on, `0` as off. If the X and Y
+    /// coordinates are out of the bounds of the display, this method call is a noop.
+    ///
+    /// See [`Ssd1306::set_pixel`].
+    pub fn set_pixel(&mut self, x: u32, y: u32, value: bool) {
+        self.canvas().set_pixel(x, y, value);
+    }
+
+    /// Clear the frame buffer.
+    pub fn clear_buffer(&mut self) {
+        self.canvas().clear(false);
+    }
+
+    /// The buffer as a [`Canvas`] to draw to.
+    fn canvas(&mut self) -> Canvas<'_> {
+        Canvas {
+            buffer: self.buffer.as_mut(),
+            dirty: &mut self.dirty,
+            size: self.size,
+            orientation: self.orientation,
+            software_orientation: self.software_orientation,
+        }
+    }
+
+    /// Set an orientation which is applied in software when pixels are drawn. Default = the
+    /// software orientation of the display the frame buffer was created for.
+    ///
+    /// See [`Ssd1306::set_software_orientation`].
+    pub fn set_software_orientation(&mut self, orientation: DisplayOrientation) {
+        self.software_orientation = orientation;
+    }
+
+    /// Get the orientation applied in software.
+    pub fn software_orientation(&self) -> DisplayOrientation {
+        self.software_orientation
+    }
 }
 
 #[maybe_async_cfg::maybe(
@@ -44,12 +214,193 @@ where
 {
     /// Create a new buffered graphics mode instance.
     pub(crate) fn new() -> Self {
+        Self::with_shadow(NoShadow)
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(feature = "async", idents(DisplaySize(async = "DisplaySizeAsync")))
+)]
+impl<SIZE, SHADOW> BufferedGraphicsMode<SIZE, SHADOW>
+where
+    SIZE: DisplaySize,
+{
+    /// Create a new buffered graphics mode instance which keeps a copy of the display RAM in
+    /// `shadow`.
+    pub(crate) fn with_shadow(shadow: SHADOW) -> Self {
         Self {
             buffer: NewZeroed::new_zeroed(),
-            min_x: 255,
-            max_x: 0,
-            min_y: 255,
-            max_y: 0,
+            dirty: [CLEAN; MAX_PAGES],
+            pending: [CLEAN; MAX_PAGES],
+            orientation: DisplayRotation::Rotate0.into(),
+            software_orientation: DisplayRotation::Rotate0.into(),
+            shadow,
+            shadow_valid: false,
+            diff_gap: REGION_COST as u8,
+        }
+    }
+}
+
+/// Buffer in the layout of the display RAM, drawn to in the orientation of a display.
+///
+/// The display and the [`FrameBuffer`] lend their buffer and changed columns to a canvas to draw.
+struct Canvas<'a> {
+    buffer: &'a mut [u8],
+    dirty: &'a mut [(u8, u8); MAX_PAGES],
+    /// Width and height of the buffer in the display RAM layout
+    size: (u8, u8),
+    orientation: DisplayOrientation,
+    software_orientation: DisplayOrientation,
+}
+
+impl Canvas<'_> {
+    /// Dimensions of the buffer in its orientation.
+    fn dimensions(&self) -> (u8, u8) {
+        match self.orientation.rotation() {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => self.size,
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (self.size.1, self.size.0),
+        }
+    }
+
+    /// Dimensions of the drawing area, taking into account the software orientation.
+    fn software_dimensions(&self) -> (u8, u8) {
+        let (width, height) = self.dimensions();
+
+        match self.software_orientation.rotation() {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (width, height),
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (height, width),
+        }
+    }
+
+    /// Adapt the buffer to a new orientation of the display, and mark the whole buffer as
+    /// changed.
+    fn reorient(&mut self, orientation: DisplayOrientation) {
+        let swapped = |orientation: DisplayOrientation| {
+            matches!(
+                orientation.rotation(),
+                DisplayRotation::Rotate90 | DisplayRotation::Rotate270
+            )
+        };
+        let clear = swapped(self.orientation) != swapped(orientation);
+        self.orientation = orientation;
+
+        if clear {
+            // The old content cannot be addressed with the new axes
+            self.clear(false);
+        } else {
+            // The content is kept, but the column offset in the display RAM may have changed
+            self.mark_all_dirty();
+        }
+    }
+
+    fn clear(&mut self, value: bool) {
+        self.buffer.fill(if value { 0xff } else { 0 });
+        self.mark_all_dirty();
+    }
+
+    fn mark_all_dirty(&mut self) {
+        let (width, height) = self.dimensions();
+        self.mark_dirty((0, 0), (u32::from(width) - 1, u32::from(height) - 1));
+    }
+
+    /// Map a point of the drawing area to the display orientation, or `None` if it is outside of
+    /// the display.
+    fn software_to_display(&self, x: u32, y: u32) -> Option<(u32, u32)> {
+        let (sw_width, sw_height) = self.software_dimensions();
+        if x >= u32::from(sw_width) || y >= u32::from(sw_height) {
+            return None;
+        }
+
+        let (x, y) = self.software_to_display_unchecked(x as i32, y as i32);
+        Some((x as u32, y as u32))
+    }
+
+    /// Map a point of the drawing area to the display orientation without checking that it is on
+    /// the display. The mapping is affine, so neighbouring points give the direction of the axes.
+    fn software_to_display_unchecked(&self, x: i32, y: i32) -> (i32, i32) {
+        let (sw_width, _) = self.software_dimensions();
+
+        let orientation = self.software_orientation;
+        let x = if orientation.is_mirrored() {
+            i32::from(sw_width) - 1 - x
+        } else {
+            x
+        };
+
+        let (width, height) = self.dimensions();
+        let (max_x, max_y) = (i32::from(width) - 1, i32::from(height) - 1);
+        match orientation.rotation() {
+            DisplayRotation::Rotate0 => (x, y),
+            DisplayRotation::Rotate90 => (max_x - y, x),
+            DisplayRotation::Rotate180 => (max_x - x, max_y - y),
+            DisplayRotation::Rotate270 => (y, max_y - x),
+        }
+    }
+
+    fn set_pixel(&mut self, x: u32, y: u32, value: bool) {
+        let (x, y) = match self.software_to_display(x, y) {
+            Some(point) => point,
+            None => return,
+        };
+
+        let (idx, bit) = self.buffer_index(x, y);
+        if let Some(byte) = self.buffer.get_mut(idx) {
+            // Set pixel value in byte
+            // Ref this comment https://stackoverflow.com/questions/47981/how-do-you-set-clear-and-toggle-a-single-bit#comment46654671_47990
+            *byte = *byte & !(1 << bit) | ((value as u8) << bit);
+
+            self.mark_dirty((x, y), (x, y));
+        }
+    }
+
+    /// Index of the buffer byte and bit of a pixel in the display orientation.
+    fn buffer_index(&self, x: u32, y: u32) -> (usize, u32) {
+        let stride = self.size.0 as usize;
+
+        match self.orientation.rotation() {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
+                let idx = ((y as usize) / 8 * stride) + (x as usize);
+                let bit = y % 8;
+
+                (idx, bit)
+            }
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
+                let idx = ((x as usize) / 8 * stride) + (y as usize);
+                let bit = x % 8;
+
+                (idx, bit)
+            }
+        }
+    }
+
+    /// Columns and rows of the buffer covered by an area in the display orientation.
+    ///
+    /// The buffer is laid out like the display RAM, with 8 rows per byte.
+    fn buffer_area(&self, min: (u32, u32), max: (u32, u32)) -> ((u32, u32), (u32, u32)) {
+        match self.orientation.rotation() {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
+                ((min.0, max.0), (min.1, max.1))
+            }
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
+                ((min.1, max.1), (min.0, max.0))
+            }
+        }
+    }
+
+    /// Mark an area in the display orientation as changed, so it is sent by the next flush.
+    fn mark_dirty(&mut self, min: (u32, u32), max: (u32, u32)) {
+        let (columns, rows) = self.buffer_area(min, max);
+
+        let pages = (rows.0 / 8) as usize..=(rows.1 / 8) as usize;
+        for span in self
+            .dirty
+            .iter_mut()
+            .take(*pages.end() + 1)
+            .skip(*pages.start())
+        {
+            span.0 = span.0.min(columns.0 as u8);
+            span.1 = span.1.max(columns.1 as u8);
         }
     }
 }
@@ -66,25 +417,45 @@ where
         )
     )
 )]
-impl<DI, SIZE> DisplayConfig for Ssd1306<DI, SIZE, BufferedGraphicsMode<SIZE>>
+impl<DI, SIZE, SHADOW> DisplayConfig for Ssd1306<DI, SIZE, BufferedGraphicsMode<SIZE, SHADOW>>
 where
     DI: WriteOnlyDataCommand,
     SIZE: DisplaySize,
+    SHADOW: AsMut<[u8]>,
 {
     type Error = DisplayError;
 
     /// Set the display rotation
     ///
-    /// This method resets the cursor but does not clear the screen.
+    /// The buffer is cleared if the axes of the display are swapped, see
+    /// [`BufferedGraphicsMode`].
     async fn set_rotation(&mut self, rot: DisplayRotation) -> Result<(), DisplayError> {
         self.set_rotation(rot).await
     }
 
+    /// Set the display orientation
+    ///
+    /// The buffer is cleared if the axes of the display are swapped, see
+    /// [`BufferedGraphicsMode`].
+    async fn set_orientation(
+        &mut self,
+        orientation: DisplayOrientation,
+    ) -> Result<(), DisplayError> {
+        self.set_orientation(orientation).await
+    }
+
     /// Initialise and clear the display in graphics mode.
     async fn init(&mut self) -> Result<(), DisplayError> {
         self.clear_impl(false);
+        self.mode.pending = [CLEAN; MAX_PAGES];
+        self.mode.shadow_valid = false;
         self.init_with_addr_mode(AddrMode::Horizontal).await
     }
+
+    async fn init_with_config(&mut self, config: InitConfig) -> Result<(), DisplayError> {
+        self.init_config = config;
+        self.init().await
+    }
 }
 
 #[maybe_async_cfg::maybe(
@@ -94,23 +465,81 @@ where
         idents(
             DisplaySize(async = "DisplaySizeAsync"),
             WriteOnlyDataCommand(async = "AsyncWriteOnlyDataCommand"),
-            BufferedGraphicsMode(async = "BufferedGraphicsModeAsync")
+            BufferedGraphicsMode(async = "BufferedGraphicsModeAsync"),
+            FrameBuffer(async = "FrameBufferAsync")
         )
     )
 )]
-impl<DI, SIZE> Ssd1306<DI, SIZE, BufferedGraphicsMode<SIZE>>
+impl<DI, SIZE, SHADOW> Ssd1306<DI, SIZE, BufferedGraphicsMode<SIZE, SHADOW>>
 where
     DI: WriteOnlyDataCommand,
     SIZE: DisplaySize,
+    SHADOW: AsMut<[u8]>,
 {
     fn clear_impl(&mut self, value: bool) {
-        self.mode.buffer.as_mut().fill(if value { 0xff } else { 0 });
+        self.update_orientation();
+        self.canvas().clear(value);
+    }
 
-        let (width, height) = self.dimensions();
-        self.mode.min_x = 0;
-        self.mode.max_x = width - 1;
-        self.mode.min_y = 0;
-        self.mode.max_y = height - 1;
+    fn mark_all_dirty(&mut self) {
+        self.canvas().mark_all_dirty();
+    }
+
+    /// The buffer as a [`Canvas`] to draw to.
+    fn canvas(&mut self) -> Canvas<'_> {
+        Canvas {
+            buffer: self.mode.buffer.as_mut(),
+            dirty: &mut self.mode.dirty,
+            size: (self.size.width(), self.size.height()),
+            orientation: self.mode.orientation,
+            software_orientation: self.mode.software_orientation,
+        }
+    }
+
+    /// Adapt the buffer to a change of the display orientation since it was last drawn to.
+    fn update_orientation(&mut self) {
+        let orientation = self.orientation;
+        if self.mode.orientation == orientation {
+            return;
+        }
+        // Abort the current `flush_step` frame, as the whole display is sent again
+        self.mode.pending = [CLEAN; MAX_PAGES];
+        self.mode.shadow_valid = false;
+
+        self.canvas().reorient(orientation);
+        self.mode.orientation = orientation;
+    }
+
+    /// Set an orientation which is applied in software when pixels are drawn, on top of the
+    /// orientation of the display. Default = not rotated or mirrored.
+    ///
+    /// The drawing area returned by `size` and `bounding_box` of the embedded-graphics
+    /// `DrawTarget` is rotated accordingly, while [`dimensions`](Ssd1306::dimensions) keeps
+    /// returning the dimensions of the display orientation. The content of the buffer is not
+    /// changed.
+    ///
+    /// ```rust
+    /// # use ssd1306::test_helpers::StubInterface;
+    /// # let interface = StubInterface;
+    /// use ssd1306::{prelude::*, Ssd1306};
+    ///
+    /// let mut display = Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0)
+    ///     .into_buffered_graphics_mode()
+    ///     .init()
+    ///     .unwrap();
+    /// display.set_software_orientation(DisplayOrientation::new(DisplayRotation::Rotate90, true));
+    ///
+    /// // The top left pixel of the 64x128 drawing area
+    /// display.set_pixel(0, 0, true);
+    /// display.flush().unwrap();
+    /// ```
+    pub fn set_software_orientation(&mut self, orientation: DisplayOrientation) {
+        self.mode.software_orientation = orientation;
+    }
+
+    /// Get the orientation applied in software.
+    pub fn software_orientation(&self) -> DisplayOrientation {
+        self.mode.software_orientation
     }
 
     /// Clear the underlying framebuffer. You need to call `disp.flush()` for any effect on the screen.
@@ -118,125 +547,350 @@ where
         self.clear_impl(false);
     }
 
+    /// Create a [`FrameBuffer`] to draw the next frame to while the display is flushed.
+    ///
+    /// The frame buffer starts with a copy of the current content of the buffer, so only the
+    /// parts drawn to are sent after the first swap.
+    pub fn frame_buffer(&mut self) -> FrameBuffer<SIZE> {
+        self.update_orientation();
+
+        let mut buffer: SIZE::Buffer = NewZeroed::new_zeroed();
+        buffer.as_mut().copy_from_slice(self.mode.buffer.as_mut());
+
+        FrameBuffer {
+            buffer,
+            dirty: [CLEAN; MAX_PAGES],
+            size: (self.size.width(), self.size.height()),
+            orientation: self.mode.orientation,
+            software_orientation: self.mode.software_orientation,
+        }
+    }
+
+    /// Exchange the buffers of the display and `frame`, so the frame drawn to `frame` is sent by
+    /// the next flush.
+    ///
+    /// Only the buffers are exchanged, which is cheap compared to sending them. `frame` then holds
+    /// the previous frame of the display. The parts of `frame` which were drawn to are compared
+    /// with the previous frame, and only the columns of each page in which the two frames differ
+    /// are sent, even if `frame` was cleared and drawn again. A `flush_step` frame which is in
+    /// progress sends the new content of the parts it has not sent yet.
+    ///
+    /// If the display orientation changed since the frame buffer was drawn to, the frame buffer
+    /// is adapted to it first, as described in [`BufferedGraphicsMode`].
+    pub fn swap(&mut self, frame: &mut FrameBuffer<SIZE>) {
+        self.update_orientation();
+        if frame.orientation != self.orientation {
+            frame.canvas().reorient(self.orientation);
+            frame.orientation = self.orientation;
+        }
+
+        core::mem::swap(&mut self.mode.buffer, &mut frame.buffer);
+
+        // Narrow the changed columns down to those in which the two frames differ, which also
+        // keeps the frame buffer tracking its differences to the display buffer
+        let stride = usize::from(self.size.width());
+        let (next, previous): (&[u8], &[u8]) = (self.mode.buffer.as_mut(), frame.buffer.as_mut());
+        for (page, (dirty, changed)) in self
+            .mode
+            .dirty
+            .iter_mut()
+            .zip(frame.dirty.iter_mut())
+            .enumerate()
+        {
+            let differs = |column: &u8| {
+                let idx = page * stride + usize::from(*column);
+                next.get(idx) != previous.get(idx)
+            };
+            let mut columns = changed.0..=changed.1;
+            *changed = match (columns.find(differs), columns.rfind(differs)) {
+                (Some(first), Some(last)) => (first, last),
+                (Some(column), None) => (column, column),
+                _ => CLEAN,
+            };
+            *dirty = (dirty.0.min(changed.0), dirty.1.max(changed.1));
+        }
+    }
+
     /// Write out data to a display.
     ///
-    /// This only updates the parts of the display that have changed since the last flush.
+    /// This only updates the parts of the display that have changed since the last flush. The
+    /// changed columns are tracked for each page of 8 rows, so changes far apart from each other,
+    /// e.g. in opposite corners of the display, are sent as separate regions.
+    ///
+    /// With a shadow buffer, only the bytes which differ from the display RAM are sent. The whole
+    /// buffer is sent after the display was initialised or its orientation was changed.
+    ///
+    /// The remaining parts of a frame started by [`flush_step`](Ssd1306::flush_step) are sent as
+    /// well.
     pub async fn flush(&mut self) -> Result<(), DisplayError> {
-        // Nothing to do if no pixels have changed since the last update
-        if self.mode.max_x < self.mode.min_x || self.mode.max_y < self.mode.min_y {
-            return Ok(());
+        self.update_orientation();
+
+        let pending = core::mem::replace(&mut self.mode.pending, [CLEAN; MAX_PAGES]);
+        for (dirty, pending) in self.mode.dirty.iter_mut().zip(pending) {
+            *dirty = (dirty.0.min(pending.0), dirty.1.max(pending.1));
         }
 
-        let (width, height) = self.dimensions();
+        if self.mode.shadow_valid {
+            return self.flush_changes().await;
+        }
 
-        // Determine which bytes need to be sent
-        let disp_min_x = self.mode.min_x;
-        let disp_min_y = self.mode.min_y;
-
-        let (disp_max_x, disp_max_y) = match self.rotation {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (
-                (self.mode.max_x + 1).min(width),
-                (self.mode.max_y | 7).min(height),
-            ),
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (
-                (self.mode.max_x | 7).min(width),
-                (self.mode.max_y + 1).min(height),
-            ),
-        };
+        // Fill the shadow buffer with the current display RAM contents
+        let fill_shadow = !self.mode.shadow.as_mut().is_empty();
+        if fill_shadow {
+            self.mark_all_dirty();
+        }
+        self.flush_regions().await?;
+        self.mode.shadow_valid = fill_shadow;
 
-        self.mode.min_x = 255;
-        self.mode.max_x = 0;
-        self.mode.min_y = 255;
-        self.mode.max_y = 0;
+        Ok(())
+    }
 
-        // Tell the display to update only the part that has changed
-        let offset_x = match self.rotation {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate270 => SIZE::OFFSETX,
-            DisplayRotation::Rotate180 | DisplayRotation::Rotate90 => {
-                // If segment remapping is flipped, we need to calculate
-                // the offset from the other edge of the display.
-                SIZE::DRIVER_COLS - SIZE::WIDTH - SIZE::OFFSETX
-            }
-        };
+    /// Send a part of the changes to the display, sending at most `max_bytes` bytes of pixel data.
+    ///
+    /// This splits a [`flush`](Ssd1306::flush) into steps which can be interleaved with other
+    /// work, e.g. to keep a control loop running on a slow bus. The first call starts a frame
+    /// with the changes made since the previous frame, and each call resumes where the previous
+    /// one stopped until [`FlushProgress::Complete`] is returned. The next call then starts a new
+    /// frame. To send at most `n` pages per call, pass `n` times the display width. The commands
+    /// which set the draw area are not counted. A `max_bytes` of 0 is treated as 1, so every call
+    /// makes progress.
+    ///
+    /// The buffer can be drawn to between the steps. Each frame sends the parts of the buffer that
+    /// were changed before it started, with their content at the time they are sent. Changes made
+    /// during a frame are sent by the next frame, even if they were already sent by this one. A
+    /// change of the display orientation aborts the frame, and the next frame sends the whole
+    /// buffer.
+    ///
+    /// With a shadow buffer, only the bytes which differ from the display RAM are sent. The shadow
+    /// buffer is only used once a complete frame was sent after the display was initialised or
+    /// its orientation was changed.
+    ///
+    /// ```rust
+    /// # use ssd1306::test_helpers::StubInterface;
+    /// # let interface = StubInterface;
+    /// use ssd1306::{prelude::*, Ssd1306};
+    ///
+    /// let mut display = Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0)
+    ///     .into_buffered_graphics_mode()
+    ///     .init()
+    ///     .unwrap();
+    ///
+    /// // Send at most 2 pages per step
+    /// while !display.flush_step(2 * 128).unwrap().is_complete() {
+    ///     // Run the control loop
+    /// }
+    /// ```
+    pub async fn flush_step(&mut self, max_bytes: usize) -> Result<FlushProgress, DisplayError> {
+        self.update_orientation();
 
-        match self.rotation {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
-                self.set_draw_area(
-                    (disp_min_x + offset_x, disp_min_y + SIZE::OFFSETY),
-                    (disp_max_x + offset_x, disp_max_y + SIZE::OFFSETY),
-                )
-                .await?;
-
-                Self::flush_buffer_chunks(
-                    &mut self.interface,
-                    self.mode.buffer.as_mut(),
-                    width as usize,
-                    (disp_min_x, disp_min_y),
-                    (disp_max_x, disp_max_y),
-                )
-                .await
+        let is_clean = |columns: &[(u8, u8)]| columns.iter().all(|&(first, last)| first > last);
+        if is_clean(&self.mode.pending) {
+            // Fill the shadow buffer with the current display RAM contents
+            if !self.mode.shadow_valid && !self.mode.shadow.as_mut().is_empty() {
+                self.mark_all_dirty();
             }
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
-                self.set_draw_area(
-                    (disp_min_y + offset_x, disp_min_x + SIZE::OFFSETY),
-                    (disp_max_y + offset_x, disp_max_x + SIZE::OFFSETY),
-                )
-                .await?;
-
-                Self::flush_buffer_chunks(
-                    &mut self.interface,
-                    self.mode.buffer.as_mut(),
-                    height as usize,
-                    (disp_min_y, disp_min_x),
-                    (disp_max_y, disp_max_x),
-                )
-                .await
+            self.mode.pending = core::mem::replace(&mut self.mode.dirty, [CLEAN; MAX_PAGES]);
+        }
+
+        let mut budget = max_bytes.max(1);
+        for page in 0..MAX_PAGES as u8 {
+            while budget > 0 {
+                let (first, last) = self.mode.pending[usize::from(page)];
+                let run = if first > last {
+                    None
+                } else if self.mode.shadow_valid {
+                    self.changed_run(page, (first, last))
+                } else {
+                    Some((first, last))
+                };
+                let Some(run) = run else {
+                    self.mode.pending[usize::from(page)] = CLEAN;
+                    break;
+                };
+
+                let end = run.0 + (usize::from(run.1 - run.0).min(budget - 1)) as u8;
+                self.flush_region((run.0, end), (page, page)).await?;
+                budget -= usize::from(end - run.0) + 1;
+                self.mode.pending[usize::from(page)] =
+                    if end == last { CLEAN } else { (end + 1, last) };
             }
         }
-    }
 
-    /// Turn a pixel on or off. A non-zero `value` is treated as on, `0` as off. If the X and Y
-    /// coordinates are out of the bounds of the display, this method call is a noop.
-    pub fn set_pixel(&mut self, x: u32, y: u32, value: bool) {
-        let value = value as u8;
-        let rotation = self.rotation;
+        if is_clean(&self.mode.pending) {
+            self.mode.shadow_valid = !self.mode.shadow.as_mut().is_empty();
+            Ok(FlushProgress::Complete)
+        } else {
+            Ok(FlushProgress::Pending)
+        }
+    }
 
-        let (idx, bit) = match rotation {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
-                let idx = ((y as usize) / 8 * SIZE::WIDTH as usize) + (x as usize);
-                let bit = y % 8;
+    /// Send the changed parts of the buffer, merged into as few regions as useful.
+    async fn flush_regions(&mut self) -> Result<(), DisplayError> {
+        let dirty = core::mem::replace(&mut self.mode.dirty, [CLEAN; MAX_PAGES]);
+        let width = |(first, last): (u8, u8)| usize::from(last - first) + 1;
 
-                (idx, bit)
+        // Merge the changed columns of consecutive pages into regions, unless sending the
+        // unchanged bytes in between costs more than setting the draw area of another region
+        let mut region: Option<((u8, u8), (u8, u8))> = None;
+        for (page, &columns) in (0..).zip(dirty.iter()) {
+            if columns.0 > columns.1 {
+                continue;
             }
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
-                let idx = ((x as usize) / 8 * SIZE::WIDTH as usize) + (y as usize);
-                let bit = x % 8;
 
-                (idx, bit)
+            region = match region {
+                Some((region_columns, pages)) => {
+                    let merged_columns = (
+                        region_columns.0.min(columns.0),
+                        region_columns.1.max(columns.1),
+                    );
+                    let merged = width(merged_columns) * width((pages.0, page));
+                    let separate =
+                        width(region_columns) * width(pages) + width(columns) + REGION_COST;
+
+                    if merged <= separate {
+                        Some((merged_columns, (pages.0, page)))
+                    } else {
+                        self.flush_region(region_columns, pages).await?;
+                        Some((columns, (page, page)))
+                    }
+                }
+                None => Some((columns, (page, page))),
+            };
+        }
+
+        match region {
+            Some((columns, pages)) => self.flush_region(columns, pages).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Send the bytes of the changed parts of the buffer which differ from the shadow buffer.
+    async fn flush_changes(&mut self) -> Result<(), DisplayError> {
+        let dirty = core::mem::replace(&mut self.mode.dirty, [CLEAN; MAX_PAGES]);
+
+        for (page, &columns) in (0..).zip(dirty.iter()) {
+            let mut column = columns.0;
+            while let Some(run) = self.changed_run(page, (column, columns.1)) {
+                self.flush_region(run, (page, page)).await?;
+                column = run.1 + 1;
             }
+        }
+
+        Ok(())
+    }
+
+    /// Find the first run of bytes between the given columns of a page which differ from the
+    /// shadow buffer. Unchanged gaps of up to [`diff_gap`](Self::set_diff_gap) bytes are included
+    /// in the run.
+    fn changed_run(&mut self, page: u8, columns: (u8, u8)) -> Option<(u8, u8)> {
+        let offset = usize::from(page) * usize::from(self.size.width());
+        let gap = self.mode.diff_gap;
+        let buffer = self.mode.buffer.as_mut();
+        let shadow = self.mode.shadow.as_mut();
+        let changed = |column: u8| {
+            let idx = offset + usize::from(column);
+            buffer.get(idx) != shadow.get(idx)
         };
 
-        if let Some(byte) = self.mode.buffer.as_mut().get_mut(idx) {
-            // Keep track of max and min values
-            self.mode.min_x = self.mode.min_x.min(x as u8);
-            self.mode.max_x = self.mode.max_x.max(x as u8);
+        let first = (columns.0..=columns.1).find(|&column| changed(column))?;
+        let mut last = first;
+        for column in first + 1..=columns.1 {
+            if changed(column) {
+                last = column;
+            } else if column - last > gap {
+                break;
+            }
+        }
 
-            self.mode.min_y = self.mode.min_y.min(y as u8);
-            self.mode.max_y = self.mode.max_y.max(y as u8);
+        Some((first, last))
+    }
 
-            // Set pixel value in byte
-            // Ref this comment https://stackoverflow.com/questions/47981/how-do-you-set-clear-and-toggle-a-single-bit#comment46654671_47990
-            *byte = *byte & !(1 << bit) | (value << bit);
+    /// Send the given columns and pages of the buffer to the display.
+    async fn flush_region(
+        &mut self,
+        columns: (u8, u8),
+        pages: (u8, u8),
+    ) -> Result<(), DisplayError> {
+        let offset_x = self.column_offset();
+        let offset_y = self.size.offset_y();
+
+        // Tell the display to update only the part that has changed
+        self.set_draw_area(
+            (columns.0 + offset_x, pages.0 * 8 + offset_y),
+            (columns.1 + 1 + offset_x, (pages.1 + 1) * 8 + offset_y),
+        )
+        .await?;
+
+        Self::flush_buffer_chunks(
+            &mut self.interface,
+            &mut self.controller,
+            self.mode.buffer.as_mut(),
+            self.size.width() as usize,
+            (columns.0, pages.0 * 8),
+            (columns.1 + 1, pages.1 * 8),
+        )
+        .await?;
+
+        // Keep the shadow buffer in sync with the display RAM
+        let stride = usize::from(self.size.width());
+        let buffer = self.mode.buffer.as_mut();
+        let shadow = self.mode.shadow.as_mut();
+        for page in usize::from(pages.0)..=usize::from(pages.1) {
+            let bytes =
+                page * stride + usize::from(columns.0)..=page * stride + usize::from(columns.1);
+            if let (Some(src), Some(dst)) = (buffer.get(bytes.clone()), shadow.get_mut(bytes)) {
+                dst.copy_from_slice(src);
+            }
         }
+
+        Ok(())
+    }
+
+    /// Turn a pixel on or off. A non-zero `value` is treated as on, `0` as off. If the X and Y
+    /// coordinates are out of the bounds of the display, this method call is a noop.
+    ///
+    /// The coordinates are rotated and mirrored by the
+    /// [software orientation](Self::set_software_orientation).
+    pub fn set_pixel(&mut self, x: u32, y: u32, value: bool) {
+        self.update_orientation();
+        self.canvas().set_pixel(x, y, value);
     }
 }
 
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(
+        feature = "async",
+        idents(
+            DisplaySize(async = "DisplaySizeAsync"),
+            WriteOnlyDataCommand(async = "AsyncWriteOnlyDataCommand"),
+            BufferedGraphicsMode(async = "BufferedGraphicsModeAsync")
+        )
+    )
+)]
+impl<DI, SIZE> Ssd1306<DI, SIZE, BufferedGraphicsMode<SIZE, SIZE::Buffer>>
+where
+    DI: WriteOnlyDataCommand,
+    SIZE: DisplaySize,
+{
+    /// Set the maximum number of unchanged bytes which are sent between two changed bytes of a
+    /// page, instead of setting the draw area again. Default = 8.
+    ///
+    /// Smaller values send fewer bytes of data, but more commands.
+    pub fn set_diff_gap(&mut self, gap: u8) {
+        self.mode.diff_gap = gap;
+    }
+}
+
+#[cfg(feature = "graphics")]
+use core::convert::Infallible;
 #[cfg(feature = "graphics")]
 use embedded_graphics_core::{
     draw_target::DrawTarget,
     geometry::Size,
-    geometry::{Dimensions, OriginDimensions},
+    geometry::{OriginDimensions, Point},
     pixelcolor::BinaryColor,
+    primitives::Rectangle,
     Pixel,
 };
 
@@ -244,6 +898,145 @@ use super::DisplayConfig;
 #[cfg(feature = "async")]
 use super::DisplayConfigAsync;
 
+#[cfg(feature = "graphics")]
+impl Canvas<'_> {
+    fn bounding_box(&self) -> Rectangle {
+        let (width, height) = self.software_dimensions();
+
+        Rectangle::new(Point::zero(), Size::new(width.into(), height.into()))
+    }
+
+    /// Clip an area of the drawing area to the display and return its top left and bottom right
+    /// corners in the display orientation, or `None` if nothing of it is visible.
+    fn clip_area(&self, area: &Rectangle) -> Option<((u32, u32), (u32, u32))> {
+        let area = area.intersection(&self.bounding_box());
+        let bottom_right = area.bottom_right()?;
+
+        let a = self.software_to_display(area.top_left.x as u32, area.top_left.y as u32)?;
+        let b = self.software_to_display(bottom_right.x as u32, bottom_right.y as u32)?;
+
+        Some(((a.0.min(b.0), a.1.min(b.1)), (a.0.max(b.0), a.1.max(b.1))))
+    }
+
+    fn draw_iter<I>(&mut self, pixels: I)
+    where
+        I: IntoIterator<Item = Pixel<BinaryColor>>,
+    {
+        let bb = self.bounding_box();
+
+        pixels
+            .into_iter()
+            .filter(|Pixel(pos, _color)| bb.contains(*pos))
+            .for_each(|Pixel(pos, color)| {
+                self.set_pixel(pos.x as u32, pos.y as u32, color.is_on());
+            });
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I)
+    where
+        I: IntoIterator<Item = BinaryColor>,
+    {
+        let clipped = area.intersection(&self.bounding_box());
+        if clipped.is_zero_sized() {
+            return;
+        }
+
+        // Column and row in the buffer of a point of the drawing area
+        let position = |x: i32, y: i32| {
+            let (x, y) = self.software_to_display_unchecked(x, y);
+            match self.orientation.rotation() {
+                DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (x, y),
+                DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (y, x),
+            }
+        };
+        let Point { x: left, y: top } = clipped.top_left;
+        let origin = position(left, top);
+        let step = |(x, y): (i32, i32)| (x - origin.0, y - origin.1);
+        let step_x = step(position(left + 1, top));
+        let step_y = step(position(left, top + 1));
+
+        // Clear the area with whole bytes, so only the lit pixels need to be set
+        self.fill_solid(&clipped, BinaryColor::Off);
+
+        let stride = i32::from(self.size.0);
+        let index = |(column, row): (i32, i32)| (row / 8 * stride + column) as usize;
+        let buffer = &mut *self.buffer;
+        let mut set = |idx: usize, bits: u8| {
+            if let Some(byte) = buffer.get_mut(idx) {
+                *byte |= bits;
+            }
+        };
+
+        // Colours outside of the display are skipped
+        let width = clipped.size.width as usize;
+        let skip_left = (left - area.top_left.x) as usize;
+        let skip_right = area.size.width as usize - width - skip_left;
+        let mut colors = colors.into_iter();
+        let skip = |colors: &mut I::IntoIter, n: usize| {
+            if n > 0 {
+                colors.nth(n - 1);
+            }
+        };
+        skip(
+            &mut colors,
+            (top - area.top_left.y) as usize * area.size.width as usize,
+        );
+
+        for row in 0..clipped.size.height as i32 {
+            skip(&mut colors, skip_left);
+
+            // Consecutive colours in the same byte of the buffer are written together
+            let mut point = (origin.0 + row * step_y.0, origin.1 + row * step_y.1);
+            let mut idx = index(point);
+            let mut bits = 0;
+            for color in colors.by_ref().take(width) {
+                if index(point) != idx {
+                    set(idx, bits);
+                    idx = index(point);
+                    bits = 0;
+                }
+                bits |= (color.is_on() as u8) << (point.1 % 8);
+                point = (point.0 + step_x.0, point.1 + step_x.1);
+            }
+            set(idx, bits);
+
+            skip(&mut colors, skip_right);
+        }
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: BinaryColor) {
+        let (min, max) = match self.clip_area(area) {
+            Some(corners) => corners,
+            None => return,
+        };
+
+        let (columns, rows) = self.buffer_area(min, max);
+        let stride = self.size.0 as usize;
+        let buffer = &mut *self.buffer;
+
+        for page in rows.0 / 8..=rows.1 / 8 {
+            // Only the rows of the area are changed in the first and last page
+            let first = if page == rows.0 / 8 { rows.0 % 8 } else { 0 };
+            let last = if page == rows.1 / 8 { rows.1 % 8 } else { 7 };
+            let mask = (0xffu8 << first) & (0xffu8 >> (7 - last));
+
+            let start = page as usize * stride + columns.0 as usize;
+            let end = page as usize * stride + columns.1 as usize;
+            if let Some(bytes) = buffer.get_mut(start..=end) {
+                for byte in bytes {
+                    if color.is_on() {
+                        *byte |= mask;
+                    } else {
+                        *byte &= !mask;
+                    }
+                }
+            }
+        }
+
+        self.mark_dirty(min, max);
+    }
+}
+
 #[cfg(feature = "graphics")]
 #[maybe_async_cfg::maybe(
     sync(keep_self),
@@ -256,10 +1049,11 @@ use super::DisplayConfigAsync;
         )
     )
 )]
-impl<DI, SIZE> DrawTarget for Ssd1306<DI, SIZE, BufferedGraphicsMode<SIZE>>
+impl<DI, SIZE, SHADOW> DrawTarget for Ssd1306<DI, SIZE, BufferedGraphicsMode<SIZE, SHADOW>>
 where
     DI: WriteOnlyDataCommand,
     SIZE: DisplaySize,
+    SHADOW: AsMut<[u8]>,
 {
     type Color = BinaryColor;
     type Error = DisplayError;
@@ -268,14 +1062,25 @@ where
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        let bb = self.bounding_box();
+        self.update_orientation();
+        self.canvas().draw_iter(pixels);
 
-        pixels
-            .into_iter()
-            .filter(|Pixel(pos, _color)| bb.contains(*pos))
-            .for_each(|Pixel(pos, color)| {
-                self.set_pixel(pos.x as u32, pos.y as u32, color.is_on());
-            });
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.update_orientation();
+        self.canvas().fill_contiguous(area, colors);
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.update_orientation();
+        self.canvas().fill_solid(area, color);
 
         Ok(())
     }
@@ -298,13 +1103,87 @@ where
         )
     )
 )]
-impl<DI, SIZE> OriginDimensions for Ssd1306<DI, SIZE, BufferedGraphicsMode<SIZE>>
+impl<DI, SIZE, SHADOW> OriginDimensions for Ssd1306<DI, SIZE, BufferedGraphicsMode<SIZE, SHADOW>>
 where
     DI: WriteOnlyDataCommand,
     SIZE: DisplaySize,
+    SHADOW: AsMut<[u8]>,
+{
+    fn size(&self) -> Size {
+        // The buffer is only adapted to the display orientation when it is drawn to
+        let (w, h) = match self.mode.software_orientation.rotation() {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => self.dimensions(),
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
+                let (width, height) = self.dimensions();
+                (height, width)
+            }
+        };
+
+        Size::new(w.into(), h.into())
+    }
+}
+
+#[cfg(feature = "graphics")]
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(feature = "async", idents(DisplaySize(async = "DisplaySizeAsync")))
+)]
+impl<SIZE> DrawTarget for FrameBuffer<SIZE>
+where
+    SIZE: DisplaySize,
+{
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.canvas().draw_iter(pixels);
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.canvas().fill_contiguous(area, colors);
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.canvas().fill_solid(area, color);
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.canvas().clear(color.is_on());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "graphics")]
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(feature = "async", idents(DisplaySize(async = "DisplaySizeAsync")))
+)]
+impl<SIZE> OriginDimensions for FrameBuffer<SIZE>
+where
+    SIZE: DisplaySize,
 {
     fn size(&self) -> Size {
-        let (w, h) = self.dimensions();
+        let swapped = |orientation: DisplayOrientation| {
+            matches!(
+                orientation.rotation(),
+                DisplayRotation::Rotate90 | DisplayRotation::Rotate270
+            )
+        };
+        let (width, height) = self.size;
+        let (w, h) = if swapped(self.orientation) == swapped(self.software_orientation) {
+            (width, height)
+        } else {
+            (height, width)
+        };
 
         Size::new(w.into(), h.into())
     }
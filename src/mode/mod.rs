@@ -3,8 +3,18 @@
 mod buffered_graphics;
 mod terminal;
 
-use crate::{command::AddrMode, rotation::DisplayRotation, size::DisplaySize, Ssd1306};
+use crate::{
+    command::AddrMode,
+    init_config::InitConfig,
+    rotation::{DisplayOrientation, DisplayRotation},
+    size::DisplaySize,
+    Ssd1306,
+};
+#[cfg(feature = "async")]
+use crate::{size::DisplaySizeAsync, Ssd1306Async};
 pub use buffered_graphics::*;
+#[cfg(feature = "async")]
+use display_interface::AsyncWriteOnlyDataCommand;
 use display_interface::{DisplayError, WriteOnlyDataCommand};
 pub use terminal::*;
 
@@ -14,53 +24,82 @@ pub trait DisplayConfig {
     /// Error.
     type Error;
 
-    /// Set display rotation.
+    /// Set display rotation, keeping the display mirrored if it was mirrored before.
     async fn set_rotation(&mut self, rotation: DisplayRotation) -> Result<(), Self::Error>;
 
+    /// Set display orientation.
+    async fn set_orientation(&mut self, orientation: DisplayOrientation)
+        -> Result<(), Self::Error>;
+
     /// Initialise and configure the display for the given mode.
     async fn init(&mut self) -> Result<(), Self::Error>;
+
+    /// Initialise and configure the display for the given mode, using custom controller settings.
+    ///
+    /// The configuration is kept and used again by subsequent calls to [`init`](Self::init).
+    async fn init_with_config(&mut self, config: InitConfig) -> Result<(), Self::Error>;
 }
 
 /// A mode with no additional functionality beyond that provided by the base [`Ssd1306`] struct.
 #[derive(Debug, Copy, Clone)]
 pub struct BasicMode;
 
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(
+        feature = "async",
+        idents(
+            DisplaySize(async = "DisplaySizeAsync"),
+            WriteOnlyDataCommand(async = "AsyncWriteOnlyDataCommand"),
+        )
+    )
+)]
 impl<DI, SIZE> Ssd1306<DI, SIZE, BasicMode>
 where
     DI: WriteOnlyDataCommand,
     SIZE: DisplaySize,
 {
     /// Clear the display.
-    pub fn clear(&mut self) -> Result<(), DisplayError> {
+    pub async fn clear(&mut self) -> Result<(), DisplayError> {
         let old_addr_mode = self.addr_mode;
         if old_addr_mode != AddrMode::Horizontal {
-            self.set_addr_mode(AddrMode::Horizontal)?;
+            self.set_addr_mode(AddrMode::Horizontal).await?;
         }
 
-        let dim = self.dimensions();
-        self.set_draw_area((0, 0), dim)?;
+        // Clear the whole display RAM, so offset and rotated displays are cleared too
+        self.set_draw_area((0, 0), (SIZE::DRIVER_COLS, SIZE::DRIVER_ROWS))
+            .await?;
 
-        let num_pixels = dim.0 as u16 * dim.1 as u16;
+        let mut num_bytes = SIZE::DRIVER_COLS as u16 * SIZE::DRIVER_ROWS as u16 / 8;
 
-        const BITS_PER_BYTE: u16 = 8;
         const BYTES_PER_BATCH: u16 = 64;
-        const PIXELS_PER_BATCH: u16 = BITS_PER_BYTE * BYTES_PER_BATCH;
 
-        // Not all screens have number of pixels divisible by 512, so add 1 to cover tail
-        let num_batches = num_pixels / PIXELS_PER_BATCH + 1;
-
-        for _ in 0..num_batches {
-            self.draw(&[0; BYTES_PER_BATCH as usize])?;
+        while num_bytes > 0 {
+            let len = num_bytes.min(BYTES_PER_BATCH);
+            self.draw(&[0; BYTES_PER_BATCH as usize][..len as usize])
+                .await?;
+            num_bytes -= len;
         }
 
         if old_addr_mode != AddrMode::Horizontal {
-            self.set_addr_mode(old_addr_mode)?;
+            self.set_addr_mode(old_addr_mode).await?;
         }
 
         Ok(())
     }
 }
 
+#[maybe_async_cfg::maybe(
+    sync(keep_self),
+    async(
+        feature = "async",
+        idents(
+            DisplayConfig(async = "DisplayConfigAsync"),
+            DisplaySize(async = "DisplaySizeAsync"),
+            WriteOnlyDataCommand(async = "AsyncWriteOnlyDataCommand"),
+        )
+    )
+)]
 impl<DI, SIZE> DisplayConfig for Ssd1306<DI, SIZE, BasicMode>
 where
     DI: WriteOnlyDataCommand,
@@ -69,12 +108,25 @@ where
     type Error = DisplayError;
 
     /// Set the display rotation.
-    fn set_rotation(&mut self, rot: DisplayRotation) -> Result<(), DisplayError> {
-        self.set_rotation(rot)
+    async fn set_rotation(&mut self, rot: DisplayRotation) -> Result<(), DisplayError> {
+        self.set_rotation(rot).await
+    }
+
+    /// Set the display orientation.
+    async fn set_orientation(
+        &mut self,
+        orientation: DisplayOrientation,
+    ) -> Result<(), DisplayError> {
+        self.set_orientation(orientation).await
     }
 
     /// Initialise in horizontal addressing mode.
-    fn init(&mut self) -> Result<(), DisplayError> {
-        self.init_with_addr_mode(AddrMode::Horizontal)
+    async fn init(&mut self) -> Result<(), DisplayError> {
+        self.init_with_addr_mode(AddrMode::Horizontal).await
+    }
+
+    async fn init_with_config(&mut self, config: InitConfig) -> Result<(), DisplayError> {
+        self.init_config = config;
+        self.init().await
     }
 }
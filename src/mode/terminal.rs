@@ -1,6 +1,15 @@
 #[cfg(feature = "async")]
+use crate::command::{CommandAsync, CommandBatchAsync};
+#[cfg(feature = "async")]
 use crate::mode::DisplayConfigAsync;
-use crate::{command::AddrMode, mode::DisplayConfig, rotation::DisplayRotation, size::*, Ssd1306};
+use crate::{
+    command::{AddrMode, Command, CommandBatch},
+    init_config::InitConfig,
+    mode::DisplayConfig,
+    rotation::{DisplayOrientation, DisplayRotation},
+    size::*,
+    Ssd1306,
+};
 #[cfg(feature = "async")]
 use crate::{size::DisplaySizeAsync, Ssd1306Async};
 use core::{cmp::min, fmt};
@@ -18,77 +27,28 @@ use display_interface::{DisplayError, WriteOnlyDataCommand};
         )
     )
 )]
-/// Extends the [`DisplaySize`](crate::size::DisplaySize) trait
-/// to include number of characters that can fit on the display.
-pub trait TerminalDisplaySize: DisplaySize {
-    /// The number of characters that can fit on the display at once (w * h / (8 * 8))
-    const CHAR_NUM: u8;
-}
-
-#[maybe_async_cfg::maybe(
-    sync(keep_self),
-    async(
-        feature = "async",
-        keep_self,
-        idents(TerminalDisplaySize(async = "TerminalDisplaySizeAsync"),)
-    )
-)]
-impl TerminalDisplaySize for DisplaySize128x64 {
-    const CHAR_NUM: u8 = 128;
-}
-
-#[maybe_async_cfg::maybe(
-    sync(keep_self),
-    async(
-        feature = "async",
-        keep_self,
-        idents(TerminalDisplaySize(async = "TerminalDisplaySizeAsync"),)
-    )
-)]
-impl TerminalDisplaySize for DisplaySize128x32 {
-    const CHAR_NUM: u8 = 64;
-}
-
-#[maybe_async_cfg::maybe(
-    sync(keep_self),
-    async(
-        feature = "async",
-        keep_self,
-        idents(TerminalDisplaySize(async = "TerminalDisplaySizeAsync"),)
-    )
-)]
-impl TerminalDisplaySize for DisplaySize96x16 {
-    const CHAR_NUM: u8 = 24;
-}
+/// Marks the [`DisplaySize`](crate::size::DisplaySize)s which can be used in [`TerminalMode`].
+///
+/// This trait is implemented for all display sizes. The display must be at least 8 pixels wide,
+/// the columns right of the last whole character are left blank.
+pub trait TerminalDisplaySize: DisplaySize {}
 
 #[maybe_async_cfg::maybe(
     sync(keep_self),
     async(
-        keep_self,
         feature = "async",
-        idents(TerminalDisplaySize(async = "TerminalDisplaySizeAsync"),)
-    )
-)]
-impl TerminalDisplaySize for DisplaySize72x40 {
-    const CHAR_NUM: u8 = 45;
-}
-
-#[maybe_async_cfg::maybe(
-    sync(keep_self),
-    async(
-        feature = "async",
-        keep_self,
-        idents(TerminalDisplaySize(async = "TerminalDisplaySizeAsync"),)
+        idents(
+            TerminalDisplaySize(async = "TerminalDisplaySizeAsync"),
+            DisplaySize(async = "DisplaySizeAsync"),
+        )
     )
 )]
-impl TerminalDisplaySize for DisplaySize64x48 {
-    const CHAR_NUM: u8 = 48;
-}
+impl<SIZE> TerminalDisplaySize for SIZE where SIZE: DisplaySize {}
 
 /// Contains the new row that the cursor has wrapped around to
 struct CursorWrapEvent(u8);
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default)]
 struct Cursor {
     col: u8,
     row: u8,
@@ -152,8 +112,6 @@ impl Cursor {
 pub enum TerminalModeError {
     /// An error occurred in the underlying interface layer
     InterfaceError(DisplayError),
-    /// The mode was used before it was initialized
-    Uninitialized,
     /// A location was specified outside the bounds of the screen
     OutOfBounds,
 }
@@ -162,7 +120,6 @@ impl fmt::Debug for TerminalModeError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         match self {
             Self::InterfaceError(_) => "InterfaceError".fmt(f),
-            Self::Uninitialized => "Uninitialized".fmt(f),
             Self::OutOfBounds => "OutOfBound".fmt(f),
         }
     }
@@ -181,7 +138,7 @@ impl From<DisplayError> for TerminalModeError {
 )]
 #[derive(Debug, Copy, Clone, Default)]
 pub struct TerminalMode {
-    cursor: Option<Cursor>,
+    cursor: Cursor,
 }
 
 #[maybe_async_cfg::maybe(
@@ -204,14 +161,13 @@ impl TerminalMode {
             DisplayConfig(async = "DisplayConfigAsync"),
             WriteOnlyDataCommand(async = "AsyncWriteOnlyDataCommand"),
             TerminalMode(async = "TerminalModeAsync"),
-            TerminalDisplaySize(async = "TerminalDisplaySizeAsync"),
         )
     )
 )]
 impl<DI, SIZE> DisplayConfig for Ssd1306<DI, SIZE, TerminalMode>
 where
     DI: WriteOnlyDataCommand,
-    SIZE: TerminalDisplaySize,
+    SIZE: DisplaySize,
 {
     type Error = TerminalModeError;
 
@@ -224,13 +180,32 @@ where
         self.reset_pos().await
     }
 
+    /// Set the display orientation
+    ///
+    /// This method resets the cursor but does not clear the screen.
+    async fn set_orientation(
+        &mut self,
+        orientation: DisplayOrientation,
+    ) -> Result<(), TerminalModeError> {
+        self.set_orientation(orientation).await?;
+        // The column offset changes with the orientation
+        self.reset_pos().await
+    }
+
     /// Initialise the display in page mode (i.e. a byte walks down a column of 8 pixels) with
     /// column 0 on the left and column _(SIZE::Width::U8 - 1)_ on the right, but no automatic line
     /// wrapping.
+    ///
+    /// Returns [`TerminalModeError::OutOfBounds`] if the display is less than 8 pixels wide.
     async fn init(&mut self) -> Result<(), TerminalModeError> {
         self.init_with_addr_mode(AddrMode::Page).await?;
         self.reset_pos().await
     }
+
+    async fn init_with_config(&mut self, config: InitConfig) -> Result<(), TerminalModeError> {
+        self.init_config = config;
+        self.init().await
+    }
 }
 
 #[maybe_async_cfg::maybe(
@@ -238,41 +213,37 @@ where
     async(
         feature = "async",
         idents(
+            Command(async = "CommandAsync"),
+            CommandBatch(async = "CommandBatchAsync"),
             DisplaySize(async = "DisplaySizeAsync"),
             DisplayConfig(async = "DisplayConfigAsync"),
             WriteOnlyDataCommand(async = "AsyncWriteOnlyDataCommand"),
             TerminalMode(async = "TerminalModeAsync"),
-            TerminalDisplaySize(async = "TerminalDisplaySizeAsync"),
         )
     )
 )]
 impl<DI, SIZE> Ssd1306<DI, SIZE, TerminalMode>
 where
     DI: WriteOnlyDataCommand,
-    SIZE: TerminalDisplaySize,
+    SIZE: DisplaySize,
 {
     /// Clear the display and reset the cursor to the top left corner
     pub async fn clear(&mut self) -> Result<(), TerminalModeError> {
         // Let the chip handle line wrapping so we can fill the screen with blanks faster
         self.set_addr_mode(AddrMode::Horizontal).await?;
 
-        let offset_x = match self.rotation() {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate270 => SIZE::OFFSETX,
-            DisplayRotation::Rotate180 | DisplayRotation::Rotate90 => {
-                // If segment remapping is flipped, we need to calculate
-                // the offset from the other edge of the display.
-                SIZE::DRIVER_COLS - SIZE::WIDTH - SIZE::OFFSETX
-            }
-        };
-        self.set_draw_area(
-            (offset_x, SIZE::OFFSETY),
-            (SIZE::WIDTH + offset_x, SIZE::HEIGHT + SIZE::OFFSETY),
-        )
-        .await?;
-
-        // Clear the display
-        for _ in 0..SIZE::CHAR_NUM {
-            self.draw(&[0; 8]).await?;
+        let offset_x = self.column_offset();
+        let offset_y = self.size.offset_y();
+        let (width, height) = (self.size.width(), self.size.height());
+        self.set_draw_area((offset_x, offset_y), (width + offset_x, height + offset_y))
+            .await?;
+
+        // Clear the display, including the columns right of the last character
+        let mut remaining = usize::from(width) * usize::from(height / 8);
+        while remaining > 0 {
+            let len = min(remaining, 8);
+            self.draw(&[0; 8][..len]).await?;
+            remaining -= len;
         }
 
         // But for normal operation we manage the line wrapping
@@ -284,16 +255,15 @@ where
     pub async fn print_char(&mut self, c: char) -> Result<(), TerminalModeError> {
         match c {
             '\n' => {
-                let CursorWrapEvent(new_line) = self.ensure_cursor()?.advance_line();
+                let CursorWrapEvent(new_line) = self.mode.cursor.advance_line();
                 self.set_position(0, new_line).await?;
             }
             '\r' => {
-                self.set_column(0).await?;
-                let (_, cur_line) = self.ensure_cursor()?.get_position();
-                self.ensure_cursor()?.set_position(0, cur_line);
+                let (_, cur_line) = self.mode.cursor.get_position();
+                self.set_position(0, cur_line).await?;
             }
             _ => {
-                let bitmap = match self.rotation {
+                let bitmap = match self.orientation.rotation() {
                     DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
                         Self::char_to_bitmap(c)
                     }
@@ -315,75 +285,59 @@ where
 
     /// Get the current cursor position, in character coordinates.
     /// This is the (column, row) that the next character will be written to.
-    pub fn position(&self) -> Result<(u8, u8), TerminalModeError> {
-        self.mode
-            .cursor
-            .as_ref()
-            .map(Cursor::get_position)
-            .ok_or(TerminalModeError::Uninitialized)
+    pub fn position(&self) -> (u8, u8) {
+        self.mode.cursor.get_position()
     }
 
     /// Set the cursor position, in character coordinates.
     /// This is the (column, row) that the next character will be written to.
     /// If the position is out of bounds, an Err will be returned.
     pub async fn set_position(&mut self, column: u8, row: u8) -> Result<(), TerminalModeError> {
-        let (width, height) = self.ensure_cursor()?.get_dimensions();
+        let (width, height) = self.mode.cursor.get_dimensions();
         if column >= width || row >= height {
             Err(TerminalModeError::OutOfBounds)
         } else {
-            let offset_x = match self.rotation() {
-                DisplayRotation::Rotate0 | DisplayRotation::Rotate270 => SIZE::OFFSETX,
-                DisplayRotation::Rotate180 | DisplayRotation::Rotate90 => {
-                    // If segment remapping is flipped, we need to calculate
-                    // the offset from the other edge of the display.
-                    SIZE::DRIVER_COLS - SIZE::WIDTH - SIZE::OFFSETX
-                }
+            let offset_x = self.column_offset();
+            let (x, y) = match self.rotation() {
+                DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (column, row),
+                DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (row, column),
             };
-            match self.rotation() {
-                DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
-                    self.set_column(offset_x + column * 8).await?;
-                    self.set_row(SIZE::OFFSETY + row * 8).await?;
-                }
-                DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
-                    self.set_column(offset_x + row * 8).await?;
-                    self.set_row(SIZE::OFFSETY + column * 8).await?;
-                }
-            }
-            self.ensure_cursor()?.set_position(column, row);
+
+            let mut batch = CommandBatch::new(&mut self.interface);
+            Command::ColStart(offset_x + x * 8).send(&mut batch).await?;
+            Command::PageStart((self.size.offset_y() + y * 8).into())
+                .send(&mut batch)
+                .await?;
+            batch.flush().await?;
+
+            self.mode.cursor.set_position(column, row);
             Ok(())
         }
     }
 
     /// Reset the draw area and move pointer to the top left corner
+    ///
+    /// Returns [`TerminalModeError::OutOfBounds`] if the display is too small for a single
+    /// character.
     async fn reset_pos(&mut self) -> Result<(), TerminalModeError> {
-        // Initialise the counter when we know it's valid
-        let (w, h) = match self.rotation() {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (SIZE::WIDTH, SIZE::HEIGHT),
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (SIZE::HEIGHT, SIZE::WIDTH),
-        };
-        self.mode.cursor = Some(Cursor::new(w, h));
+        // Initialise the counter for the current rotation
+        let (w, h) = self.dimensions();
+        if w < 8 || h < 8 {
+            return Err(TerminalModeError::OutOfBounds);
+        }
+        self.mode.cursor = Cursor::new(w, h);
 
         // Reset cursor position
         self.set_position(0, 0).await
     }
 
     /// Advance the cursor, automatically wrapping lines and/or screens if necessary
-    /// Takes in an already-unwrapped cursor to avoid re-unwrapping
     async fn advance_cursor(&mut self) -> Result<(), TerminalModeError> {
-        let cursor = self.ensure_cursor()?;
-
-        cursor.advance();
-        let (c, r) = cursor.get_position();
+        self.mode.cursor.advance();
+        let (c, r) = self.mode.cursor.get_position();
         self.set_position(c, r).await
     }
 
-    fn ensure_cursor(&mut self) -> Result<&mut Cursor, TerminalModeError> {
-        self.mode
-            .cursor
-            .as_mut()
-            .ok_or(TerminalModeError::Uninitialized)
-    }
-
     fn char_to_bitmap(input: char) -> [u8; 8] {
         const CHARS: [[u8; 6]; 95] = [
             // !
@@ -607,7 +561,7 @@ where
 impl<DI, SIZE> Ssd1306Async<DI, SIZE, TerminalModeAsync>
 where
     DI: AsyncWriteOnlyDataCommand,
-    SIZE: TerminalDisplaySizeAsync,
+    SIZE: DisplaySizeAsync,
 {
     /// Write a string slice to the display
     pub async fn write_str(&mut self, s: &str) -> Result<(), TerminalModeError> {
@@ -621,7 +575,7 @@ where
 impl<DI, SIZE> fmt::Write for Ssd1306<DI, SIZE, TerminalMode>
 where
     DI: WriteOnlyDataCommand,
-    SIZE: TerminalDisplaySize,
+    SIZE: DisplaySize,
 {
     fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
         s.chars().map(move |c| self.print_char(c)).last();
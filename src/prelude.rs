@@ -6,11 +6,14 @@ pub use display_interface_spi::SPIInterface;
 
 pub use super::{
     brightness::Brightness,
+    init_config::InitConfig,
     mode::DisplayConfig,
-    rotation::DisplayRotation,
+    rotation::{DisplayOrientation, DisplayRotation},
     size::{
-        DisplaySize, DisplaySize128x32, DisplaySize128x64, DisplaySize64x48, DisplaySize72x40,
-        DisplaySize96x16,
+        DisplaySize, DisplaySize128x128Sh1107, DisplaySize128x32, DisplaySize128x32Ssd1305,
+        DisplaySize128x64, DisplaySize128x64Sh1106, DisplaySize128x64Ssd1305,
+        DisplaySize64x128Sh1107, DisplaySize64x32, DisplaySize64x48, DisplaySize72x40,
+        DisplaySize96x16, DynamicSize,
     },
 };
 
@@ -1,7 +1,7 @@
-//! Display rotation.
+//! Display rotation and orientation.
 
 /// Display rotation.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum DisplayRotation {
     /// No rotation, normal display
     Rotate0,
@@ -12,3 +12,63 @@ pub enum DisplayRotation {
     /// Rotate 270 degrees clockwise
     Rotate270,
 }
+
+/// Display orientation: a rotation, optionally mirrored.
+///
+/// A mirrored display is flipped left to right after it is rotated, so the eight orientations
+/// cover every way a panel can be mounted.
+///
+/// ```rust
+/// use ssd1306::rotation::{DisplayOrientation, DisplayRotation};
+///
+/// let orientation = DisplayOrientation::new(DisplayRotation::Rotate90, true);
+/// assert_eq!(orientation.rotation(), DisplayRotation::Rotate90);
+/// assert!(orientation.is_mirrored());
+///
+/// assert_eq!(
+///     DisplayOrientation::from(DisplayRotation::Rotate180),
+///     DisplayOrientation::new(DisplayRotation::Rotate180, false)
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DisplayOrientation {
+    rotation: DisplayRotation,
+    mirrored: bool,
+}
+
+impl DisplayOrientation {
+    /// Create an orientation from a rotation and whether the rotated display is mirrored.
+    pub const fn new(rotation: DisplayRotation, mirrored: bool) -> Self {
+        Self { rotation, mirrored }
+    }
+
+    /// The rotation of the display.
+    pub const fn rotation(self) -> DisplayRotation {
+        self.rotation
+    }
+
+    /// Whether the rotated display is mirrored left to right.
+    pub const fn is_mirrored(self) -> bool {
+        self.mirrored
+    }
+
+    /// Segment remap and COM scan direction of SSD1306 style drivers for the orientation.
+    pub(crate) fn scan_direction(self) -> (bool, bool) {
+        match (self.rotation, self.mirrored) {
+            (DisplayRotation::Rotate0, false) => (true, true),
+            (DisplayRotation::Rotate90, false) => (false, true),
+            (DisplayRotation::Rotate180, false) => (false, false),
+            (DisplayRotation::Rotate270, false) => (true, false),
+            (DisplayRotation::Rotate0, true) => (false, true),
+            (DisplayRotation::Rotate90, true) => (false, false),
+            (DisplayRotation::Rotate180, true) => (true, false),
+            (DisplayRotation::Rotate270, true) => (true, true),
+        }
+    }
+}
+
+impl From<DisplayRotation> for DisplayOrientation {
+    fn from(rotation: DisplayRotation) -> Self {
+        Self::new(rotation, false)
+    }
+}
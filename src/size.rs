@@ -1,8 +1,14 @@
 //! Display size.
 
-use super::command::Command;
+use super::{
+    command::Command,
+    controller::{
+        Controller as DisplayController, Sh1106Controller, Sh1107Controller, Ssd1305Controller,
+        Ssd1306Controller,
+    },
+};
 #[cfg(feature = "async")]
-use super::command::CommandAsync;
+use super::{command::CommandAsync, controller::ControllerAsync as DisplayControllerAsync};
 #[cfg(feature = "async")]
 use display_interface::AsyncWriteOnlyDataCommand;
 use display_interface::{DisplayError, WriteOnlyDataCommand};
@@ -27,14 +33,25 @@ impl<const N: usize> NewZeroed for [u8; N] {
     sync(keep_self),
     async(
         feature = "async",
-        idents(WriteOnlyDataCommand(async = "AsyncWriteOnlyDataCommand"))
+        idents(
+            DisplayController(async = "DisplayControllerAsync"),
+            WriteOnlyDataCommand(async = "AsyncWriteOnlyDataCommand"),
+        )
     )
 )]
 pub trait DisplaySize {
     /// Width in pixels
+    ///
+    /// For sizes selected at runtime, like [`DynamicSize`], this is the largest supported width
+    /// which the [`Buffer`](Self::Buffer) is sized for, not the width of the panel. Use
+    /// [`width`](Self::width) for the width of the panel.
     const WIDTH: u8;
 
     /// Height in pixels
+    ///
+    /// For sizes selected at runtime, like [`DynamicSize`], this is the largest supported height
+    /// which the [`Buffer`](Self::Buffer) is sized for, not the height of the panel. Use
+    /// [`height`](Self::height) for the height of the panel.
     const HEIGHT: u8;
 
     /// Maximum width supported by the display driver
@@ -49,6 +66,11 @@ pub trait DisplaySize {
     /// Vertical offset in pixels
     const OFFSETY: u8 = 0;
 
+    /// Display driver chip
+    ///
+    /// See the [`controller`](crate::controller) module for the supported drivers.
+    type Controller: DisplayController;
+
     /// Size of framebuffer. Because the display is monochrome, this is
     /// width * height / 8
     type Buffer: AsMut<[u8]> + NewZeroed;
@@ -59,6 +81,121 @@ pub trait DisplaySize {
     /// and [`Command::InternalIref`]
     /// for more information
     async fn configure(&self, iface: &mut impl WriteOnlyDataCommand) -> Result<(), DisplayError>;
+
+    /// Width of this display in pixels. Defaults to [`WIDTH`](Self::WIDTH).
+    fn width(&self) -> u8 {
+        Self::WIDTH
+    }
+
+    /// Height of this display in pixels. Defaults to [`HEIGHT`](Self::HEIGHT).
+    fn height(&self) -> u8 {
+        Self::HEIGHT
+    }
+
+    /// Horizontal offset of this display in pixels. Defaults to [`OFFSETX`](Self::OFFSETX).
+    fn offset_x(&self) -> u8 {
+        Self::OFFSETX
+    }
+
+    /// Vertical offset of this display in pixels. Defaults to [`OFFSETY`](Self::OFFSETY).
+    fn offset_y(&self) -> u8 {
+        Self::OFFSETY
+    }
+}
+
+/// Size information for a display which is only known at runtime
+///
+/// This allows the same firmware to drive different SSD1306 displays, e.g. depending on a board
+/// variant read at startup. The framebuffer of
+/// [`BufferedGraphicsMode`](crate::mode::BufferedGraphicsMode) is always sized for the largest
+/// display of 128x64 pixels, so [`DisplaySize::WIDTH`] and [`DisplaySize::HEIGHT`] are always
+/// 128 and 64. The size of the panel is given by [`DisplaySize::width`] and
+/// [`DisplaySize::height`].
+///
+/// ```rust
+/// # use ssd1306::test_helpers::StubInterface;
+/// # let interface = StubInterface;
+/// # let small_panel = true;
+/// use ssd1306::{prelude::*, Ssd1306};
+///
+/// let size = if small_panel {
+///     DynamicSize::new(128, 32).com_pins(false, false)
+/// } else {
+///     DynamicSize::new(128, 64)
+/// };
+///
+/// let display = Ssd1306::new(interface, size, DisplayRotation::Rotate0)
+///     .into_buffered_graphics_mode()
+///     .init()
+///     .unwrap();
+/// assert_eq!(display.dimensions(), (128, 32));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DynamicSize {
+    width: u8,
+    height: u8,
+    offset_x: u8,
+    offset_y: u8,
+    com_pins: (bool, bool),
+}
+
+impl DynamicSize {
+    /// Create the size information for a display of `width` x `height` pixels.
+    ///
+    /// The width must be between 1 and 128, the height a multiple of 8 between 8 and 64. The
+    /// display has no offset and uses the alternative COM pin configuration without left/right
+    /// remap, like most of the fixed sizes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width or height is out of range.
+    pub const fn new(width: u8, height: u8) -> Self {
+        assert!(0 < width && width <= 128, "Width must be between 1 and 128");
+        assert!(
+            0 < height && height <= 64 && height % 8 == 0,
+            "Height must be a multiple of 8 between 8 and 64"
+        );
+
+        Self {
+            width,
+            height,
+            offset_x: 0,
+            offset_y: 0,
+            com_pins: (true, false),
+        }
+    }
+
+    /// Set the offset of the display in the driver RAM in pixels. Default = `(0, 0)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display does not fit into the driver RAM at this offset.
+    pub const fn offset(self, x: u8, y: u8) -> Self {
+        assert!(
+            self.width as u16 + x as u16 <= 128,
+            "Display must fit into the 128 driver columns"
+        );
+        assert!(
+            self.height as u16 + y as u16 <= 64,
+            "Display must fit into the 64 driver rows"
+        );
+
+        Self {
+            offset_x: x,
+            offset_y: y,
+            ..self
+        }
+    }
+
+    /// Set the COM pin hardware configuration. Default = `(true, false)`.
+    ///
+    /// See [`Command::ComPinConfig`].
+    pub const fn com_pins(self, alternative: bool, lr_remap: bool) -> Self {
+        Self {
+            com_pins: (alternative, lr_remap),
+            ..self
+        }
+    }
 }
 
 maybe_async_cfg::content! {
@@ -77,6 +214,7 @@ pub struct DisplaySize128x64;
 impl DisplaySize for DisplaySize128x64 {
     const WIDTH: u8 = 128;
     const HEIGHT: u8 = 64;
+    type Controller = Ssd1306Controller;
     type Buffer = [u8; <Self as DisplaySize>::WIDTH as usize *
         <Self as DisplaySize>::HEIGHT as usize / 8];
 
@@ -95,6 +233,7 @@ pub struct DisplaySize128x32;
 impl DisplaySize for DisplaySize128x32 {
     const WIDTH: u8 = 128;
     const HEIGHT: u8 = 32;
+    type Controller = Ssd1306Controller;
     type Buffer = [u8; <Self as DisplaySize>::WIDTH as usize *
         <Self as DisplaySize>::HEIGHT as usize / 8];
 
@@ -113,6 +252,7 @@ pub struct DisplaySize96x16;
 impl DisplaySize for DisplaySize96x16 {
     const WIDTH: u8 = 96;
     const HEIGHT: u8 = 16;
+    type Controller = Ssd1306Controller;
     type Buffer = [u8; <Self as DisplaySize>::WIDTH as usize *
         <Self as DisplaySize>::HEIGHT as usize / 8];
 
@@ -133,6 +273,7 @@ impl DisplaySize for DisplaySize72x40 {
     const HEIGHT: u8 = 40;
     const OFFSETX: u8 = 28;
     const OFFSETY: u8 = 0;
+    type Controller = Ssd1306Controller;
     type Buffer = [u8; <Self as DisplaySize>::WIDTH as usize *
         <Self as DisplaySize>::HEIGHT as usize / 8];
 
@@ -154,6 +295,7 @@ impl DisplaySize for DisplaySize64x48 {
     const HEIGHT: u8 = 48;
     const OFFSETX: u8 = 32;
     const OFFSETY: u8 = 0;
+    type Controller = Ssd1306Controller;
     type Buffer = [u8; <Self as DisplaySize>::WIDTH as usize *
         <Self as DisplaySize>::HEIGHT as usize / 8];
 
@@ -174,6 +316,101 @@ impl DisplaySize for DisplaySize64x32 {
     const HEIGHT: u8 = 32;
     const OFFSETX: u8 = 32;
     const OFFSETY: u8 = 0;
+    type Controller = Ssd1306Controller;
+    type Buffer = [u8; <Self as DisplaySize>::WIDTH as usize *
+        <Self as DisplaySize>::HEIGHT as usize / 8];
+
+    async fn configure(
+        &self,
+        iface: &mut impl WriteOnlyDataCommand,
+    ) -> Result<(), DisplayError> {
+        Command::ComPinConfig(true, false).send(iface).await
+    }
+}
+
+/// Size information for the common 1.3" 128x64 modules with an SH1106 driver
+///
+/// The SH1106 has a 132 column display RAM, see [`Sh1106Controller`].
+#[derive(Debug, Copy, Clone)]
+pub struct DisplaySize128x64Sh1106;
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async", keep_self))]
+impl DisplaySize for DisplaySize128x64Sh1106 {
+    const WIDTH: u8 = 128;
+    const HEIGHT: u8 = 64;
+    const DRIVER_COLS: u8 = 132;
+    const OFFSETX: u8 = 2;
+    const OFFSETY: u8 = 0;
+    type Controller = Sh1106Controller;
+    type Buffer = [u8; <Self as DisplaySize>::WIDTH as usize *
+        <Self as DisplaySize>::HEIGHT as usize / 8];
+
+    async fn configure(
+        &self,
+        iface: &mut impl WriteOnlyDataCommand,
+    ) -> Result<(), DisplayError> {
+        Command::ComPinConfig(true, false).send(iface).await
+    }
+}
+
+/// Size information for the 128x128 modules with an SH1107 driver
+///
+/// The SH1107 has a 128x128 display RAM, see [`Sh1107Controller`].
+#[derive(Debug, Copy, Clone)]
+pub struct DisplaySize128x128Sh1107;
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async", keep_self))]
+impl DisplaySize for DisplaySize128x128Sh1107 {
+    const WIDTH: u8 = 128;
+    const HEIGHT: u8 = 128;
+    const DRIVER_ROWS: u8 = 128;
+    type Controller = Sh1107Controller;
+    type Buffer = [u8; <Self as DisplaySize>::WIDTH as usize *
+        <Self as DisplaySize>::HEIGHT as usize / 8];
+
+    async fn configure(
+        &self,
+        _iface: &mut impl WriteOnlyDataCommand,
+    ) -> Result<(), DisplayError> {
+        Ok(())
+    }
+}
+
+/// Size information for the 64x128 modules with an SH1107 driver
+///
+/// These panels are connected to 64 of the SH1107 commons, so with
+/// [`DisplayRotation::Rotate90`](crate::rotation::DisplayRotation::Rotate90) they show 128x64
+/// pixels.
+#[derive(Debug, Copy, Clone)]
+pub struct DisplaySize64x128Sh1107;
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async", keep_self))]
+impl DisplaySize for DisplaySize64x128Sh1107 {
+    const WIDTH: u8 = 64;
+    const HEIGHT: u8 = 128;
+    const DRIVER_ROWS: u8 = 128;
+    type Controller = Sh1107Controller;
+    type Buffer = [u8; <Self as DisplaySize>::WIDTH as usize *
+        <Self as DisplaySize>::HEIGHT as usize / 8];
+
+    async fn configure(
+        &self,
+        _iface: &mut impl WriteOnlyDataCommand,
+    ) -> Result<(), DisplayError> {
+        Ok(())
+    }
+}
+
+/// Size information for the common 128x32 variants with an SSD1305 driver
+///
+/// The SSD1305 has a 132 column display RAM, see [`Ssd1305Controller`].
+#[derive(Debug, Copy, Clone)]
+pub struct DisplaySize128x32Ssd1305;
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async", keep_self))]
+impl DisplaySize for DisplaySize128x32Ssd1305 {
+    const WIDTH: u8 = 128;
+    const HEIGHT: u8 = 32;
+    const DRIVER_COLS: u8 = 132;
+    const OFFSETX: u8 = 4;
+    const OFFSETY: u8 = 0;
+    type Controller = Ssd1305Controller;
     type Buffer = [u8; <Self as DisplaySize>::WIDTH as usize *
         <Self as DisplaySize>::HEIGHT as usize / 8];
 
@@ -185,4 +422,204 @@ impl DisplaySize for DisplaySize64x32 {
     }
 }
 
+/// Size information for the common 128x64 variants with an SSD1305 driver
+///
+/// The SSD1305 has a 132 column display RAM, see [`Ssd1305Controller`].
+#[derive(Debug, Copy, Clone)]
+pub struct DisplaySize128x64Ssd1305;
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async", keep_self))]
+impl DisplaySize for DisplaySize128x64Ssd1305 {
+    const WIDTH: u8 = 128;
+    const HEIGHT: u8 = 64;
+    const DRIVER_COLS: u8 = 132;
+    const OFFSETX: u8 = 4;
+    const OFFSETY: u8 = 0;
+    type Controller = Ssd1305Controller;
+    type Buffer = [u8; <Self as DisplaySize>::WIDTH as usize *
+        <Self as DisplaySize>::HEIGHT as usize / 8];
+
+    async fn configure(
+        &self,
+        iface: &mut impl WriteOnlyDataCommand,
+    ) -> Result<(), DisplayError> {
+        Command::ComPinConfig(true, false).send(iface).await
+    }
+}
+
+#[maybe_async_cfg::maybe(sync(keep_self), async(feature = "async", keep_self))]
+impl DisplaySize for DynamicSize {
+    const WIDTH: u8 = 128;
+    const HEIGHT: u8 = 64;
+    type Controller = Ssd1306Controller;
+    type Buffer = [u8; <Self as DisplaySize>::WIDTH as usize *
+        <Self as DisplaySize>::HEIGHT as usize / 8];
+
+    async fn configure(
+        &self,
+        iface: &mut impl WriteOnlyDataCommand,
+    ) -> Result<(), DisplayError> {
+        let (alternative, lr_remap) = self.com_pins;
+        Command::ComPinConfig(alternative, lr_remap).send(iface).await
+    }
+
+    fn width(&self) -> u8 {
+        self.width
+    }
+
+    fn height(&self) -> u8 {
+        self.height
+    }
+
+    fn offset_x(&self) -> u8 {
+        self.offset_x
+    }
+
+    fn offset_y(&self) -> u8 {
+        self.offset_y
+    }
+}
+
 } // content
+
+/// Define a custom display size.
+///
+/// This generates a unit struct implementing [`DisplaySize`], and the async `DisplaySizeAsync` if
+/// the `async` feature is enabled, so panels which are not supported by the crate can be used
+/// without copying a size implementation.
+///
+/// `width`, `height` and the COM pin configuration `com_pins: (alternative, lr_remap)` are
+/// required, see [`Command::ComPinConfig`]. The other settings are optional, but must be given
+/// in this order:
+///
+/// - `offset: (x, y)`: offset of the display in the driver RAM, default `(0, 0)`.
+/// - `driver: (cols, rows)`: size of the driver RAM, default `(128, 64)`.
+/// - `controller`: display driver chip, default
+///   [`Ssd1306Controller`](crate::controller::Ssd1306Controller).
+/// - `iref: (enable, current)`: send [`Command::InternalIref`] during initialisation.
+///
+/// ```rust
+/// # use ssd1306::test_helpers::StubInterface;
+/// # let interface = StubInterface;
+/// use ssd1306::{prelude::*, Ssd1306};
+///
+/// ssd1306::display_size! {
+///     /// 0.42" 72x40 display with a different offset
+///     pub struct DisplaySize72x40Clone {
+///         width: 72,
+///         height: 40,
+///         offset: (30, 12),
+///         com_pins: (true, false),
+///         iref: (true, true),
+///     }
+/// }
+///
+/// let display = Ssd1306::new(interface, DisplaySize72x40Clone, DisplayRotation::Rotate0)
+///     .into_terminal_mode()
+///     .init()
+///     .unwrap();
+/// assert_eq!(display.dimensions(), (72, 40));
+/// ```
+#[macro_export]
+macro_rules! display_size {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            width: $width:expr,
+            height: $height:expr,
+            $(offset: ($offset_x:expr, $offset_y:expr),)?
+            $(driver: ($driver_cols:expr, $driver_rows:expr),)?
+            $(controller: $controller:ty,)?
+            com_pins: ($alternative:expr, $lr_remap:expr),
+            $(iref: ($iref_enable:expr, $iref_current:expr),)?
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Copy, Clone)]
+        $vis struct $name;
+
+        impl $crate::size::DisplaySize for $name {
+            const WIDTH: u8 = $width;
+            const HEIGHT: u8 = $height;
+            $(
+                const OFFSETX: u8 = $offset_x;
+                const OFFSETY: u8 = $offset_y;
+            )?
+            $(
+                const DRIVER_COLS: u8 = $driver_cols;
+                const DRIVER_ROWS: u8 = $driver_rows;
+            )?
+            type Controller = $crate::__display_size_controller!($($controller)?);
+            type Buffer = [u8; $width as usize * $height as usize / 8];
+
+            fn configure(
+                &self,
+                iface: &mut impl $crate::__private::WriteOnlyDataCommand,
+            ) -> Result<(), $crate::__private::DisplayError> {
+                $crate::command::Command::ComPinConfig($alternative, $lr_remap).send(iface)?;
+                $(
+                    $crate::command::Command::InternalIref($iref_enable, $iref_current)
+                        .send(iface)?;
+                )?
+                Ok(())
+            }
+        }
+
+        $crate::__display_size_async! {
+            $name, ($alternative, $lr_remap) $(, ($iref_enable, $iref_current))?
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __display_size_controller {
+    () => {
+        $crate::controller::Ssd1306Controller
+    };
+    ($controller:ty) => {
+        $controller
+    };
+}
+
+#[cfg(feature = "async")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __display_size_async {
+    (
+        $name:ident, ($alternative:expr, $lr_remap:expr)
+        $(, ($iref_enable:expr, $iref_current:expr))?
+    ) => {
+        impl $crate::size::DisplaySizeAsync for $name {
+            const WIDTH: u8 = <$name as $crate::size::DisplaySize>::WIDTH;
+            const HEIGHT: u8 = <$name as $crate::size::DisplaySize>::HEIGHT;
+            const DRIVER_COLS: u8 = <$name as $crate::size::DisplaySize>::DRIVER_COLS;
+            const DRIVER_ROWS: u8 = <$name as $crate::size::DisplaySize>::DRIVER_ROWS;
+            const OFFSETX: u8 = <$name as $crate::size::DisplaySize>::OFFSETX;
+            const OFFSETY: u8 = <$name as $crate::size::DisplaySize>::OFFSETY;
+            type Controller = <$name as $crate::size::DisplaySize>::Controller;
+            type Buffer = <$name as $crate::size::DisplaySize>::Buffer;
+
+            async fn configure(
+                &self,
+                iface: &mut impl $crate::__private::AsyncWriteOnlyDataCommand,
+            ) -> Result<(), $crate::__private::DisplayError> {
+                $crate::command::CommandAsync::ComPinConfig($alternative, $lr_remap)
+                    .send(iface)
+                    .await?;
+                $(
+                    $crate::command::CommandAsync::InternalIref($iref_enable, $iref_current)
+                        .send(iface)
+                        .await?;
+                )?
+                Ok(())
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "async"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __display_size_async {
+    ($($tokens:tt)*) => {};
+}
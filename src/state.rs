@@ -0,0 +1,26 @@
+//! Display initialisation state.
+//!
+//! [`Ssd1306::new`] and the `into_*_mode` methods return an [`Uninitialized`] display, which can
+//! only be reset, converted into another mode or initialised. Initialising it with
+//! [`init`](crate::Ssd1306::init) returns the [`Initialized`] display, which can be drawn to. This makes
+//! drawing to a display before it is initialised a compile error:
+//!
+//! ```rust,compile_fail
+//! # use ssd1306::test_helpers::StubInterface;
+//! # let interface = StubInterface;
+//! use ssd1306::{prelude::*, Ssd1306};
+//!
+//! let mut display = Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0)
+//!     .into_buffered_graphics_mode();
+//! display.flush().unwrap();
+//! ```
+//!
+//! [`Ssd1306::new`]: crate::Ssd1306::new
+
+/// State of a display which has not been initialised yet.
+#[derive(Debug, Copy, Clone)]
+pub struct Uninitialized;
+
+/// State of an initialised display. This is the default state of [`Ssd1306`](crate::Ssd1306).
+#[derive(Debug, Copy, Clone)]
+pub struct Initialized;
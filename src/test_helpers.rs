@@ -2,6 +2,7 @@
 
 use display_interface::{DisplayError, WriteOnlyDataCommand};
 use embedded_hal::{
+    delay::DelayNs,
     digital::{ErrorType, OutputPin},
     i2c,
     spi::{self, SpiBus},
@@ -94,6 +95,14 @@ impl OutputPin for PinStub {
     }
 }
 
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub struct DelayStub;
+
+impl DelayNs for DelayStub {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
 pub struct StubInterface;
@@ -0,0 +1,80 @@
+//! Async `BasicMode`.
+
+use core::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+use ssd1306::{
+    emulator::Emulator,
+    prelude::*,
+    size::{DisplaySize, DisplaySizeAsync},
+    Ssd1306Async,
+};
+
+/// Run a future which never waits, as the emulator completes every transfer immediately.
+fn block_on<F: Future>(future: F) -> F::Output {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(
+        |_| RawWaker::new(core::ptr::null(), &VTABLE),
+        |_| {},
+        |_| {},
+        |_| {},
+    );
+    // SAFETY: the vtable functions do nothing, so any data pointer is valid
+    let waker = unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) };
+
+    let mut future = pin!(future);
+    match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future did not complete"),
+    }
+}
+
+/// Fill the whole driver RAM, then check that `clear` clears all of it.
+fn check_clear<SIZE>(size: SIZE)
+where
+    SIZE: DisplaySize + DisplaySizeAsync + Copy,
+{
+    let (cols, rows) = (
+        <SIZE as DisplaySize>::DRIVER_COLS,
+        <SIZE as DisplaySize>::DRIVER_ROWS,
+    );
+
+    let ram_len = usize::from(cols) * usize::from(rows) / 8;
+
+    let emulator = block_on(async {
+        let mut display =
+            Ssd1306Async::new(Emulator::for_size(size), size, DisplayRotation::Rotate0)
+                .init()
+                .await
+                .unwrap();
+        display.set_draw_area((0, 0), (cols, rows)).await.unwrap();
+        display.draw(&vec![0xff; ram_len]).await.unwrap();
+
+        display.release()
+    });
+    assert_eq!(emulator.gddram().len(), ram_len);
+    assert!(emulator.gddram().iter().all(|&byte| byte == 0xff));
+
+    let emulator = block_on(async {
+        let mut display = Ssd1306Async::new(emulator, size, DisplayRotation::Rotate0)
+            .init()
+            .await
+            .unwrap();
+        display.clear().await.unwrap();
+
+        display.release()
+    });
+    assert!(emulator.gddram().iter().all(|&byte| byte == 0));
+}
+
+#[test]
+fn clear() {
+    check_clear(DisplaySize128x64);
+    check_clear(DisplaySize72x40);
+    check_clear(DisplaySize128x64Sh1106);
+    check_clear(DisplaySize128x32Ssd1305);
+    check_clear(DisplaySize128x64Ssd1305);
+    check_clear(DisplaySize64x128Sh1107);
+}
@@ -0,0 +1,120 @@
+//! Display settings given to the [`Ssd1306Builder`].
+
+mod common;
+
+use common::{assert_frame, draw_test_pattern, to_pbm, ROTATIONS};
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use ssd1306::{command::Command, emulator::Emulator, prelude::*, Ssd1306, Ssd1306Builder};
+
+/// Interface which records the commands sent to it.
+#[derive(Default)]
+struct RecordingInterface {
+    commands: Vec<u8>,
+}
+
+impl WriteOnlyDataCommand for RecordingInterface {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        match cmd {
+            DataFormat::U8(bytes) => self.commands.extend_from_slice(bytes),
+            _ => return Err(DisplayError::DataFormatNotImplemented),
+        }
+        Ok(())
+    }
+
+    fn send_data(&mut self, _buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        Ok(())
+    }
+}
+
+/// The builder must set up the display like the equivalent calls after `init`.
+#[test]
+fn builder() {
+    for (rotation, name) in ROTATIONS {
+        let mut display = Ssd1306::new(
+            Emulator::for_size(DisplaySize72x40),
+            DisplaySize72x40,
+            rotation,
+        )
+        .into_buffered_graphics_mode()
+        .init()
+        .unwrap();
+        display.set_mirror(true).unwrap();
+        display.set_invert(true).unwrap();
+        draw_test_pattern(&mut display);
+        let expected = to_pbm(&display.release());
+
+        let mut display =
+            Ssd1306Builder::new(Emulator::for_size(DisplaySize72x40), DisplaySize72x40)
+                .rotation(rotation)
+                .mirror(true)
+                .invert(true)
+                .into_buffered_graphics_mode()
+                .init()
+                .unwrap();
+        draw_test_pattern(&mut display);
+        let actual = to_pbm(&display.release());
+
+        assert_frame(name, &expected, &actual);
+    }
+}
+
+/// Settings changed at runtime must survive initialising the display again.
+#[test]
+fn reinit_keeps_runtime_settings() {
+    let mut display = Ssd1306Builder::new(Emulator::for_size(DisplaySize128x64), DisplaySize128x64)
+        .display_on(false)
+        .invert(false)
+        .init()
+        .unwrap();
+    display.set_display_on(true).unwrap();
+    display.set_invert(true).unwrap();
+    display.set_brightness(Brightness::DIM).unwrap();
+
+    display.init().unwrap();
+    display.clear().unwrap();
+
+    let emulator = display.release();
+    assert!(emulator.is_display_on());
+    // The RAM is clear, so every pixel is lit only while the display is inverted
+    assert!(emulator.pixel(0, 0));
+}
+
+/// The builder settings must be part of the initialisation sequence, instead of being changed by
+/// separate commands after it.
+#[test]
+fn single_init_sequence() {
+    let display = Ssd1306Builder::new(RecordingInterface::default(), DisplaySize128x64)
+        .rotation(DisplayRotation::Rotate180)
+        .mirror(true)
+        .brightness(Brightness::DIM)
+        .invert(true)
+        .display_on(false)
+        .init()
+        .unwrap();
+
+    let bytes = display.release().commands;
+    let mut commands = Vec::new();
+    let mut rest = &bytes[..];
+    while !rest.is_empty() {
+        let (_, len) = Command::parse(rest).unwrap();
+        commands.push(rest[..len].to_vec());
+        rest = &rest[len..];
+    }
+
+    // The display is turned off first and kept off
+    assert_eq!(commands.first(), Some(&vec![0xAE]));
+    assert!(!commands.contains(&vec![0xAF]));
+    // Every setting is sent once, with the value given to the builder
+    let (phase1, _) = InitConfig::new().get_precharge();
+    for setting in [
+        vec![0xA1],
+        vec![0xC0],
+        vec![0xD9, Brightness::DIM.precharge() << 4 | phase1],
+        vec![0x81, Brightness::DIM.contrast()],
+        vec![0xA7],
+    ] {
+        let opcode = |command: &&Vec<u8>| command[0] & !1 == setting[0] & !1;
+        let sent: Vec<_> = commands.iter().filter(opcode).collect();
+        assert_eq!(sent, [&setting], "{:02X?}", commands);
+    }
+}
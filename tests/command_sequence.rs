@@ -0,0 +1,62 @@
+//! Sending command sequences over I2C.
+
+use core::convert::Infallible;
+
+use embedded_hal::i2c::{ErrorType, I2c, Operation, SevenBitAddress};
+use ssd1306::{
+    command::{AddrMode, Command, CommandSequence},
+    I2CDisplayInterface,
+};
+
+/// I2C bus which records the bytes of each write.
+#[derive(Default)]
+struct RecordingI2c {
+    writes: Vec<Vec<u8>>,
+}
+
+impl ErrorType for RecordingI2c {
+    type Error = Infallible;
+}
+
+impl I2c<SevenBitAddress> for RecordingI2c {
+    fn transaction(
+        &mut self,
+        _address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        for operation in operations {
+            if let Operation::Write(bytes) = operation {
+                self.writes.push(bytes.to_vec());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn send_over_i2c() {
+    const INIT: CommandSequence<16> = CommandSequence::new()
+        .with(Command::DisplayOn(false))
+        .with(Command::DisplayClockDiv(0x8, 0x0))
+        .with(Command::Multiplex(63))
+        .with(Command::AddressMode(AddrMode::Horizontal))
+        .with(Command::ColumnAddress(0, 127))
+        .with(Command::DisplayOn(true));
+
+    let mut interface = I2CDisplayInterface::new(RecordingI2c::default());
+    INIT.send(&mut interface).unwrap();
+
+    let writes = interface.release().writes;
+    assert!(writes.len() > 1);
+
+    let mut sent = Vec::new();
+    for write in &writes {
+        // Control byte for commands, followed by whole commands
+        assert_eq!(write[0], 0x00);
+        assert!(write.len() - 1 <= Command::MAX_LEN);
+        assert!(Command::decode(&write[1..]).all(|command| command.is_ok()));
+        sent.extend_from_slice(&write[1..]);
+    }
+    assert_eq!(sent, INIT.as_bytes());
+}
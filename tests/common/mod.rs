@@ -0,0 +1,100 @@
+//! Helpers shared by the integration tests.
+
+use core::fmt::Write;
+use std::fmt;
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, MonoTextStyle},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{Line, PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+};
+use ssd1306::{emulator::Emulator, mode::BufferedGraphicsMode, prelude::*, Ssd1306};
+
+pub const ROTATIONS: [(DisplayRotation, &str); 4] = [
+    (DisplayRotation::Rotate0, "rotate0"),
+    (DisplayRotation::Rotate90, "rotate90"),
+    (DisplayRotation::Rotate180, "rotate180"),
+    (DisplayRotation::Rotate270, "rotate270"),
+];
+
+/// Render the visible panel image as a plain PBM file.
+pub fn to_pbm(emulator: &Emulator) -> String {
+    let mut pbm = String::new();
+
+    writeln!(pbm, "P1").unwrap();
+    writeln!(pbm, "{} {}", emulator.width(), emulator.height()).unwrap();
+    for y in 0..emulator.height() {
+        for x in 0..emulator.width() {
+            pbm.push(if emulator.pixel(x, y) { '1' } else { '0' });
+        }
+        pbm.push('\n');
+    }
+
+    pbm
+}
+
+/// Pretty print a PBM image for failure messages.
+pub struct Preview<'a>(pub &'a str);
+
+impl fmt::Display for Preview<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.0.lines().skip(2) {
+            let row: String = line
+                .chars()
+                .map(|c| if c == '1' { '#' } else { '.' })
+                .collect();
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+/// Assert that two PBM images are equal, printing both if they are not.
+pub fn assert_frame(name: &str, expected: &str, actual: &str) {
+    assert_eq!(
+        expected,
+        actual,
+        "{}\nexpected:\n{}\nactual:\n{}",
+        name,
+        Preview(expected),
+        Preview(actual)
+    );
+}
+
+/// Draw an asymmetric test pattern in two flushes, so both full and partial updates are covered.
+pub fn draw_test_pattern<SIZE>(display: &mut Ssd1306<Emulator, SIZE, BufferedGraphicsMode<SIZE>>)
+where
+    SIZE: DisplaySize,
+{
+    let outline = PrimitiveStyle::with_stroke(BinaryColor::On, 1);
+    let fill = PrimitiveStyle::with_fill(BinaryColor::On);
+    let bb = display.bounding_box();
+
+    bb.into_styled(outline).draw(display).unwrap();
+    Rectangle::new(Point::new(2, 2), Size::new(4, 4))
+        .into_styled(fill)
+        .draw(display)
+        .unwrap();
+    Text::with_baseline(
+        "F7",
+        Point::new(7, 2),
+        MonoTextStyle::new(&FONT_6X10, BinaryColor::On),
+        Baseline::Top,
+    )
+    .draw(display)
+    .unwrap();
+    display.flush().unwrap();
+
+    let corner = bb.bottom_right().unwrap();
+    Line::new(corner - Point::new(10, 2), corner - Point::new(2, 2))
+        .into_styled(outline)
+        .draw(display)
+        .unwrap();
+    Rectangle::new(corner - Point::new(4, 5), Size::new(2, 2))
+        .into_styled(fill)
+        .draw(display)
+        .unwrap();
+    display.flush().unwrap();
+}
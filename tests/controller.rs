@@ -0,0 +1,119 @@
+//! Controllers implemented outside of the crate.
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use ssd1306::{
+    command::{AddrMode, Command, CommandSequence, VcomhLevel},
+    controller::Controller,
+    prelude::*,
+    Ssd1306Builder,
+};
+
+/// Interface which records the commands sent to it.
+#[derive(Default)]
+struct RecordingInterface {
+    commands: Vec<u8>,
+}
+
+impl WriteOnlyDataCommand for RecordingInterface {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        match cmd {
+            DataFormat::U8(bytes) => self.commands.extend_from_slice(bytes),
+            _ => return Err(DisplayError::DataFormatNotImplemented),
+        }
+        Ok(())
+    }
+
+    fn send_data(&mut self, _buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        Ok(())
+    }
+}
+
+/// A driver which is initialised from the configuration by its own sequence.
+#[derive(Debug, Default, Copy, Clone)]
+struct ConfigController;
+
+impl Controller for ConfigController {
+    fn init<SIZE: DisplaySize>(
+        &mut self,
+        iface: &mut impl WriteOnlyDataCommand,
+        _size: &SIZE,
+        config: &InitConfig,
+        _orientation: DisplayOrientation,
+        _mode: AddrMode,
+    ) -> Result<(), DisplayError> {
+        let (frequency, divide_ratio) = config.get_clock();
+        Command::DisplayClockDiv(frequency, divide_ratio).send(iface)?;
+        Command::DisplayOffset(config.get_display_offset()).send(iface)?;
+        Command::StartLine(config.get_start_line()).send(iface)?;
+        if let Some(enable) = config.get_charge_pump() {
+            Command::ChargePump(enable).send(iface)?;
+        }
+        if let Some((alternative, lr_remap)) = config.get_com_pins() {
+            Command::ComPinConfig(alternative, lr_remap).send(iface)?;
+        }
+        // The brightness replaces precharge phase 2
+        let (phase1, phase2) = config.get_precharge();
+        let brightness = config
+            .get_brightness()
+            .unwrap_or(Brightness::custom(phase2, 0x7F));
+        Command::PreChargePeriod(phase1, brightness.precharge()).send(iface)?;
+        Command::Contrast(brightness.contrast()).send(iface)?;
+        if let Some(level) = config.get_vcomh() {
+            Command::VcomhDeselect(level).send(iface)?;
+        }
+        Command::Invert(config.get_invert()).send(iface)?;
+        Command::DisplayOn(config.get_display_on()).send(iface)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct ConfigDisplay;
+
+impl DisplaySize for ConfigDisplay {
+    const WIDTH: u8 = 128;
+    const HEIGHT: u8 = 64;
+    type Controller = ConfigController;
+    type Buffer = [u8; 128 * 64 / 8];
+
+    fn configure(&self, _iface: &mut impl WriteOnlyDataCommand) -> Result<(), DisplayError> {
+        Ok(())
+    }
+}
+
+#[test]
+fn init_from_config() {
+    let config = InitConfig::new()
+        .clock(0x7, 0x1)
+        .display_offset(2)
+        .start_line(5)
+        .charge_pump(false)
+        .com_pins(false, true)
+        .precharge(0x3, 0x4)
+        .vcomh(VcomhLevel::V077);
+
+    let display = Ssd1306Builder::new(RecordingInterface::default(), ConfigDisplay)
+        .init_config(config)
+        .brightness(Brightness::DIM)
+        .invert(true)
+        .display_on(false)
+        .init()
+        .unwrap();
+
+    let mut expected = CommandSequence::<32>::new();
+    for command in [
+        Command::DisplayClockDiv(0x7, 0x1),
+        Command::DisplayOffset(2),
+        Command::StartLine(5),
+        Command::ChargePump(false),
+        Command::ComPinConfig(false, true),
+        Command::PreChargePeriod(0x3, 0x2),
+        Command::Contrast(0x2F),
+        Command::VcomhDeselect(VcomhLevel::V077),
+        Command::Invert(true),
+        Command::DisplayOn(false),
+    ] {
+        expected.push(command).unwrap();
+    }
+
+    assert_eq!(display.release().commands, expected.as_bytes());
+}
@@ -0,0 +1,29 @@
+//! Setting the draw area on drivers without horizontal addressing.
+
+use display_interface::DisplayError;
+use ssd1306::{emulator::Emulator, prelude::*, Ssd1306};
+
+#[test]
+fn empty_page_window() {
+    let mut display = Ssd1306::new(
+        Emulator::for_size(DisplaySize128x64Sh1106),
+        DisplaySize128x64Sh1106,
+        DisplayRotation::Rotate0,
+    )
+    .into_buffered_graphics_mode()
+    .init()
+    .unwrap();
+
+    for (start, end) in [((0, 0), (0, 8)), ((10, 8), (4, 16)), ((0, 8), (128, 8))] {
+        assert!(matches!(
+            display.set_draw_area(start, end),
+            Err(DisplayError::OutOfBoundsError)
+        ));
+    }
+
+    // A valid area can still be drawn to
+    display.set_draw_area((0, 0), (8, 8)).unwrap();
+    display.set_pixel(0, 0, true);
+    display.flush().unwrap();
+    assert!(display.release().pixel(0, 0));
+}
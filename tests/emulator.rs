@@ -0,0 +1,101 @@
+//! GDDRAM contents of the emulator after raw command and data streams.
+
+use display_interface::{DataFormat::U8, DisplayError, WriteOnlyDataCommand};
+use ssd1306::{emulator::Emulator, prelude::*, Ssd1306};
+
+/// The bytes of `page` from column `start` on, `len` bytes long.
+fn ram(emulator: &Emulator, columns: usize, page: usize, start: usize, len: usize) -> &[u8] {
+    &emulator.gddram()[page * columns + start..][..len]
+}
+
+#[test]
+fn horizontal_addressing() {
+    let mut emulator = Emulator::new();
+
+    // Horizontal addressing, columns 2 to 4, pages 1 to 2
+    emulator
+        .send_commands(U8(&[0x20, 0x00, 0x21, 2, 4, 0x22, 1, 2]))
+        .unwrap();
+    // The pointer wraps around to the start of the area after the sixth byte
+    emulator.send_data(U8(&[1, 2, 3, 4, 5, 6, 7, 8])).unwrap();
+
+    assert_eq!(ram(&emulator, 128, 0, 0, 6), [0; 6]);
+    assert_eq!(ram(&emulator, 128, 1, 1, 5), [0, 7, 8, 3, 0]);
+    assert_eq!(ram(&emulator, 128, 2, 1, 5), [0, 4, 5, 6, 0]);
+    assert_eq!(ram(&emulator, 128, 3, 0, 6), [0; 6]);
+}
+
+#[test]
+fn vertical_addressing() {
+    let mut emulator = Emulator::new();
+
+    // Vertical addressing, columns 10 to 11, pages 6 to 7
+    emulator
+        .send_commands(U8(&[0x20, 0x01, 0x21, 10, 11, 0x22, 6, 7]))
+        .unwrap();
+    emulator.send_data(U8(&[1, 2, 3, 4, 5])).unwrap();
+
+    assert_eq!(ram(&emulator, 128, 6, 9, 4), [0, 5, 3, 0]);
+    assert_eq!(ram(&emulator, 128, 7, 9, 4), [0, 2, 4, 0]);
+}
+
+#[test]
+fn page_addressing() {
+    let mut emulator = Emulator::new();
+
+    // Page addressing is the reset default. Page 7, column 126 set with its lower and upper
+    // nibble.
+    emulator.send_commands(U8(&[0xB7, 0x0E, 0x17])).unwrap();
+    // The column wraps around to the start of the same page
+    emulator.send_data(U8(&[1, 2, 3])).unwrap();
+
+    assert_eq!(ram(&emulator, 128, 7, 124, 4), [0, 0, 1, 2]);
+    assert_eq!(ram(&emulator, 128, 7, 0, 2), [3, 0]);
+    assert!(emulator.gddram()[..7 * 128].iter().all(|byte| *byte == 0));
+}
+
+#[test]
+fn sh1106_ram() {
+    let mut emulator = Emulator::for_size(DisplaySize128x64Sh1106);
+    assert_eq!(emulator.gddram().len(), 132 * 8);
+
+    // The SH1106 has no horizontal addressing
+    assert!(matches!(
+        emulator.send_commands(U8(&[0x20, 0x00])),
+        Err(DisplayError::InvalidFormatError)
+    ));
+
+    // Page 3, column 130 of the 132 columns
+    emulator.send_commands(U8(&[0xB3, 0x02, 0x18])).unwrap();
+    emulator.send_data(U8(&[1, 2, 3])).unwrap();
+
+    assert_eq!(ram(&emulator, 132, 3, 128, 4), [0, 0, 1, 2]);
+    assert_eq!(ram(&emulator, 132, 3, 0, 2), [3, 0]);
+}
+
+#[test]
+fn sh1106_page_window() {
+    let mut display = Ssd1306::new(
+        Emulator::for_size(DisplaySize128x64Sh1106),
+        DisplaySize128x64Sh1106,
+        DisplayRotation::Rotate0,
+    )
+    .init()
+    .unwrap();
+
+    // The last two columns of pages 1 and 2, written page by page by the controller
+    display.set_draw_area((130, 8), (132, 24)).unwrap();
+    display.draw(&[1, 2, 3, 4, 5, 6]).unwrap();
+
+    let emulator = display.release();
+    assert_eq!(ram(&emulator, 132, 1, 128, 4), [0, 0, 5, 6]);
+    assert_eq!(ram(&emulator, 132, 2, 128, 4), [0, 0, 3, 4]);
+    assert_eq!(ram(&emulator, 132, 1, 0, 1), [0]);
+    assert_eq!(ram(&emulator, 132, 3, 128, 4), [0; 4]);
+}
+
+#[test]
+#[should_panic(expected = "Panel does not fit into the controller columns")]
+fn panel_outside_ram() {
+    Emulator::with_panel(128, 64, 1, 0);
+}
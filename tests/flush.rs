@@ -0,0 +1,154 @@
+//! Sending the changed parts of a buffered display.
+
+use embedded_graphics::{
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+};
+use ssd1306::{emulator::Emulator, prelude::*, Ssd1306};
+
+/// Changes in opposite corners of the display must be sent as separate regions.
+#[test]
+fn sparse_flush() {
+    let mut display = Ssd1306::new(
+        Emulator::for_size(DisplaySize128x64),
+        DisplaySize128x64,
+        DisplayRotation::Rotate0,
+    )
+    .into_buffered_graphics_mode()
+    .init()
+    .unwrap();
+    display.flush().unwrap();
+
+    let icon = PrimitiveStyle::with_fill(BinaryColor::On);
+    Rectangle::new(Point::new(0, 0), Size::new(8, 8))
+        .into_styled(icon)
+        .draw(&mut display)
+        .unwrap();
+    Rectangle::new(Point::new(120, 56), Size::new(8, 8))
+        .into_styled(icon)
+        .draw(&mut display)
+        .unwrap();
+
+    display.flush().unwrap();
+
+    let emulator = display.release();
+    assert!(emulator.pixel(0, 0) && emulator.pixel(127, 63));
+    // The whole cleared display, then two 8x8 regions
+    assert_eq!(emulator.stats().data_bytes, 1024 + 2 * 8);
+}
+
+#[test]
+fn shadow_flush() {
+    fn draw<D: DrawTarget<Color = BinaryColor>>(display: &mut D, pixels: &[i32])
+    where
+        D::Error: core::fmt::Debug,
+    {
+        display.clear(BinaryColor::Off).unwrap();
+        Rectangle::new(Point::new(0, 0), Size::new(8, 8))
+            .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+            .draw(display)
+            .unwrap();
+        display
+            .draw_iter(
+                pixels
+                    .iter()
+                    .map(|&x| Pixel(Point::new(x, 0), BinaryColor::On)),
+            )
+            .unwrap();
+    }
+
+    let mut display = Ssd1306::new(
+        Emulator::for_size(DisplaySize128x64),
+        DisplaySize128x64,
+        DisplayRotation::Rotate0,
+    )
+    .into_buffered_graphics_mode_with_shadow()
+    .init()
+    .unwrap();
+    display.flush().unwrap();
+
+    draw(&mut display, &[]);
+    display.flush().unwrap();
+    // Redrawing the same content sends nothing
+    draw(&mut display, &[]);
+    display.flush().unwrap();
+    // Changes more than 8 bytes apart are sent separately
+    draw(&mut display, &[20, 30, 40, 44]);
+    display.flush().unwrap();
+
+    let mut expected = Ssd1306::new(
+        Emulator::for_size(DisplaySize128x64),
+        DisplaySize128x64,
+        DisplayRotation::Rotate0,
+    )
+    .into_buffered_graphics_mode()
+    .init()
+    .unwrap();
+    draw(&mut expected, &[20, 30, 40, 44]);
+    expected.flush().unwrap();
+
+    let emulator = display.release();
+    assert_eq!(emulator.gddram(), expected.release().gddram());
+    // The whole cleared display, the 8x8 rectangle, then columns 20, 30 and 40 to 44
+    assert_eq!(emulator.stats().data_bytes, 1024 + 8 + 1 + 1 + 5);
+}
+
+#[test]
+fn flush_step() {
+    let mut display = Ssd1306::new(
+        Emulator::for_size(DisplaySize128x64),
+        DisplaySize128x64,
+        DisplayRotation::Rotate0,
+    )
+    .into_buffered_graphics_mode_with_shadow()
+    .init()
+    .unwrap();
+    display.set_pixel(0, 0, true);
+
+    // The whole display, in steps of at most 100 bytes
+    let mut steps = 1;
+    while !display.flush_step(100).unwrap().is_complete() {
+        if steps == 1 {
+            // Page 0 was already sent, so this is sent by the next frame
+            display.set_pixel(1, 0, true);
+            // The last page is still pending and sent by this frame
+            display.set_pixel(127, 63, true);
+        }
+        steps += 1;
+    }
+    assert_eq!(steps, 11);
+
+    // Only the byte changed during the previous frame
+    assert!(display.flush_step(100).unwrap().is_complete());
+    assert!(display.flush_step(100).unwrap().is_complete());
+
+    let emulator = display.release();
+    assert!(emulator.pixel(0, 0) && emulator.pixel(1, 0) && emulator.pixel(127, 63));
+    assert_eq!(emulator.stats().data_bytes, 1024 + 1);
+}
+
+/// A budget of 0 bytes must still send one byte per step.
+#[test]
+fn flush_step_zero() {
+    let mut display = Ssd1306::new(
+        Emulator::for_size(DisplaySize128x64),
+        DisplaySize128x64,
+        DisplayRotation::Rotate0,
+    )
+    .into_buffered_graphics_mode()
+    .init()
+    .unwrap();
+    display.set_pixel(0, 0, true);
+
+    let mut steps = 1;
+    while !display.flush_step(0).unwrap().is_complete() {
+        steps += 1;
+        assert!(steps <= 1024, "flush_step(0) does not make progress");
+    }
+    assert_eq!(steps, 1024);
+
+    let emulator = display.release();
+    assert!(emulator.pixel(0, 0));
+    assert_eq!(emulator.stats().data_bytes, 1024);
+}
@@ -0,0 +1,107 @@
+//! Drawing the next frame while the previous one is sent.
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, MonoTextStyle},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+};
+use ssd1306::{emulator::Emulator, prelude::*, Ssd1306};
+
+#[test]
+fn frame_buffer() {
+    fn draw<D: DrawTarget<Color = BinaryColor>>(display: &mut D, offset: i32)
+    where
+        D::Error: core::fmt::Debug,
+    {
+        Rectangle::new(Point::new(offset, 2), Size::new(20, 10))
+            .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+            .draw(display)
+            .unwrap();
+        Text::with_baseline(
+            "F7",
+            Point::new(offset + 3, 3),
+            MonoTextStyle::new(&FONT_6X10, BinaryColor::On),
+            Baseline::Top,
+        )
+        .draw(display)
+        .unwrap();
+    }
+
+    let size = DisplaySize128x32Ssd1305;
+    let orientation = DisplayOrientation::new(DisplayRotation::Rotate90, true);
+    let new_display = || {
+        let mut display = Ssd1306::new(Emulator::for_size(size), size, DisplayRotation::Rotate180)
+            .into_buffered_graphics_mode()
+            .init()
+            .unwrap();
+        display.set_software_orientation(orientation);
+        display
+    };
+
+    let expected = |offset: i32| {
+        let mut display = new_display();
+        draw(&mut display, offset);
+        display.flush().unwrap();
+        display.release().gddram().to_vec()
+    };
+
+    // Display RAM after the given number of swaps
+    let swapped = |swaps: usize| {
+        let mut display = new_display();
+        display.flush().unwrap();
+        let mut frame = display.frame_buffer();
+        assert_eq!(frame.software_orientation(), orientation);
+        assert_eq!(frame.bounding_box(), display.bounding_box());
+
+        draw(&mut frame, 0);
+        display.swap(&mut frame);
+        // Draw the next frame while the previous one is sent. The frame buffer now holds the
+        // cleared frame the display started with.
+        while !display.flush_step(16).unwrap().is_complete() {
+            draw(&mut frame, 5);
+        }
+        for _ in 1..swaps {
+            display.swap(&mut frame);
+            display.flush().unwrap();
+        }
+
+        display.release().gddram().to_vec()
+    };
+
+    assert_eq!(swapped(1), expected(0));
+    // The first frame must be removed from the display
+    assert_eq!(swapped(2), expected(5));
+    // The frames are exchanged, so the first one is shown again
+    assert_eq!(swapped(3), expected(0));
+}
+
+/// Clearing and redrawing the frame buffer must only send the columns which differ from the
+/// previous frame.
+#[test]
+fn frame_buffer_changes() {
+    let mut display = Ssd1306::new(
+        Emulator::for_size(DisplaySize128x64),
+        DisplaySize128x64,
+        DisplayRotation::Rotate0,
+    )
+    .into_buffered_graphics_mode()
+    .init()
+    .unwrap();
+    display.flush().unwrap();
+    let mut frame = display.frame_buffer();
+
+    // A pixel moving along the top row
+    for x in 0..4 {
+        frame.clear_buffer();
+        frame.set_pixel(x, 0, true);
+        display.swap(&mut frame);
+        display.flush().unwrap();
+    }
+
+    let emulator = display.release();
+    assert!(emulator.pixel(3, 0) && !emulator.pixel(2, 0));
+    // The whole cleared display, the first pixel, then the old and new column of each move
+    assert_eq!(emulator.stats().data_bytes, 1024 + 1 + 3 * 2);
+}
@@ -0,0 +1,123 @@
+//! Commands sent during initialisation with an `InitConfig`.
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use ssd1306::{
+    command::{Command, VcomhLevel},
+    prelude::*,
+    Ssd1306,
+};
+
+/// Interface which records the commands sent to it.
+#[derive(Default)]
+struct RecordingInterface {
+    commands: Vec<u8>,
+}
+
+impl WriteOnlyDataCommand for RecordingInterface {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        match cmd {
+            DataFormat::U8(bytes) => self.commands.extend_from_slice(bytes),
+            _ => return Err(DisplayError::DataFormatNotImplemented),
+        }
+        Ok(())
+    }
+
+    fn send_data(&mut self, _buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        Ok(())
+    }
+}
+
+/// The commands sent to initialise a display of `size` with `config`, each with its arguments.
+fn init_commands<SIZE: DisplaySize>(size: SIZE, config: InitConfig) -> Vec<Vec<u8>> {
+    let display = Ssd1306::new(
+        RecordingInterface::default(),
+        size,
+        DisplayRotation::Rotate0,
+    )
+    .init_with_config(config)
+    .unwrap();
+
+    let bytes = display.release().commands;
+    let mut commands = Vec::new();
+    let mut rest = &bytes[..];
+    while !rest.is_empty() {
+        let (_, len) = Command::parse(rest).unwrap();
+        commands.push(rest[..len].to_vec());
+        rest = &rest[len..];
+    }
+    commands
+}
+
+#[test]
+fn ssd1309() {
+    let opcodes = |commands: &[Vec<u8>]| commands.iter().map(|c| c[0]).collect::<Vec<_>>();
+
+    let commands = init_commands(DisplaySize128x64, InitConfig::new());
+    assert!(opcodes(&commands).contains(&0x8D));
+    assert!(opcodes(&commands).contains(&0xDB));
+
+    let commands = init_commands(DisplaySize128x64, InitConfig::ssd1309());
+    // No charge pump and no VCOMH level
+    assert!(!opcodes(&commands).contains(&0x8D));
+    assert!(!opcodes(&commands).contains(&0xDB));
+    // Display clock and precharge periods at the SSD1309 reset values
+    assert!(commands.contains(&vec![0xD5, 0x70]));
+    assert!(commands.contains(&vec![0xD9, 0x22]));
+}
+
+#[test]
+fn ssd1305() {
+    let opcodes = |commands: &[Vec<u8>]| commands.iter().map(|c| c[0]).collect::<Vec<_>>();
+
+    let config = InitConfig::new().vcomh(VcomhLevel::V077);
+    for commands in [
+        init_commands(DisplaySize128x32Ssd1305, config),
+        init_commands(DisplaySize128x64Ssd1305, config),
+    ] {
+        // No charge pump, and the VCOMH level is not sent in the SSD1306 encoding
+        assert!(!opcodes(&commands).contains(&0x8D));
+        assert!(!opcodes(&commands).contains(&0xDB));
+        assert!(commands.contains(&vec![0xD9, 0x21]));
+    }
+
+    let commands = init_commands(DisplaySize128x64, config);
+    assert!(commands.contains(&vec![0xDB, 0x20]));
+}
+
+#[test]
+fn driver_rows() {
+    fn init<SIZE: DisplaySize>(size: SIZE, config: InitConfig) -> Result<(), DisplayError> {
+        Ssd1306::new(
+            RecordingInterface::default(),
+            size,
+            DisplayRotation::Rotate0,
+        )
+        .init_with_config(config)
+        .map(drop)
+    }
+
+    // The SSD1306 has 64 rows
+    for config in [
+        InitConfig::new().display_offset(64),
+        InitConfig::new().start_line(64),
+    ] {
+        assert!(matches!(
+            init(DisplaySize128x64, config),
+            Err(DisplayError::OutOfBoundsError)
+        ));
+    }
+    let commands = init_commands(
+        DisplaySize128x64,
+        InitConfig::new().display_offset(63).start_line(63),
+    );
+    assert!(commands.contains(&vec![0xD3, 63]));
+    assert!(commands.contains(&vec![0x7F]));
+
+    // The SH1107 has 128
+    let commands = init_commands(DisplaySize128x128Sh1107, InitConfig::new().start_line(127));
+    assert!(commands.contains(&vec![0xDC, 127]));
+    assert!(matches!(
+        init(DisplaySize128x128Sh1107, InitConfig::new().start_line(128)),
+        Err(DisplayError::OutOfBoundsError)
+    ));
+}
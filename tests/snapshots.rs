@@ -0,0 +1,391 @@
+//! Golden image tests.
+//!
+//! Every display size is driven through the [`Emulator`] in each rotation and display mode, and the
+//! image visible on the emulated panel is compared against a PBM file in `tests/snapshots`.
+//!
+//! Verify the snapshots with
+//!
+//! ```text
+//! cargo test --features emulator --test snapshots --target x86_64-unknown-linux-gnu
+//! ```
+//!
+//! (the target must be given explicitly because `.cargo/config.toml` defaults to an embedded
+//! target), and regenerate them after an intentional change in output with
+//!
+//! ```text
+//! UPDATE_SNAPSHOTS=1 cargo test --features emulator --test snapshots --target x86_64-unknown-linux-gnu
+//! ```
+
+mod common;
+
+use core::fmt::Write;
+use std::{fs, path::PathBuf};
+
+use common::{assert_frame, draw_test_pattern, to_pbm, Preview, ROTATIONS};
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*, primitives::Rectangle};
+use ssd1306::{emulator::Emulator, prelude::*, Ssd1306};
+
+/// Compare an image against its golden file, or overwrite the golden file if `UPDATE_SNAPSHOTS`
+/// is set.
+fn check_snapshot(name: &str, emulator: &Emulator) -> Result<(), String> {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "snapshots", name]
+        .iter()
+        .collect::<PathBuf>()
+        .with_extension("pbm");
+    let actual = to_pbm(emulator);
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return Ok(());
+    }
+
+    let expected = fs::read_to_string(&path).map_err(|e| {
+        format!(
+            "{}: cannot read snapshot ({}), run with UPDATE_SNAPSHOTS=1 to create it",
+            path.display(),
+            e
+        )
+    })?;
+
+    if expected != actual {
+        return Err(format!(
+            "{} does not match\nexpected:\n{}\nactual:\n{}",
+            name,
+            Preview(&expected),
+            Preview(&actual)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check all rotations, reporting every mismatch at once.
+fn check_rotations(mode: &str, size_name: &str, render: impl Fn(DisplayRotation) -> Emulator) {
+    let failures: Vec<String> = ROTATIONS
+        .iter()
+        .filter_map(|(rotation, rotation_name)| {
+            let name = format!("{}_{}_{}", mode, size_name, rotation_name);
+            check_snapshot(&name, &render(*rotation)).err()
+        })
+        .collect();
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// Draw the [`draw_test_pattern`] on a new buffered display.
+fn buffered_graphics<SIZE>(size: SIZE, rotation: DisplayRotation) -> Emulator
+where
+    SIZE: DisplaySize + Copy,
+{
+    let mut display = Ssd1306::new(Emulator::for_size(size), size, rotation)
+        .into_buffered_graphics_mode()
+        .init()
+        .unwrap();
+    draw_test_pattern(&mut display);
+
+    display.release()
+}
+
+/// Print text with line breaks, wrapping and explicit positioning.
+fn terminal<SIZE>(size: SIZE, rotation: DisplayRotation) -> Emulator
+where
+    SIZE: DisplaySize + Copy,
+{
+    let mut display = Ssd1306::new(Emulator::for_size(size), size, rotation)
+        .into_terminal_mode()
+        .init()
+        .unwrap();
+    display.clear().unwrap();
+
+    display.write_str("Hi,\nSSD1306 0123456789").unwrap();
+    let (columns, rows) = {
+        let (w, h) = display.dimensions();
+        (w / 8, h / 8)
+    };
+    display.set_position(columns - 1, rows - 1).unwrap();
+    display.print_char('#').unwrap();
+
+    display.release()
+}
+
+macro_rules! snapshot_tests {
+    ($($size:ident: $buffered:ident $(, $terminal:ident)?;)*) => {
+        $(
+            #[test]
+            fn $buffered() {
+                let size_name = &stringify!($size)["DisplaySize".len()..];
+                check_rotations("buffered", size_name, |rotation| {
+                    buffered_graphics($size, rotation)
+                });
+            }
+
+            $(
+                #[test]
+                fn $terminal() {
+                    let size_name = &stringify!($size)["DisplaySize".len()..];
+                    check_rotations("terminal", size_name, |rotation| terminal($size, rotation));
+                }
+            )?
+        )*
+    };
+}
+
+snapshot_tests! {
+    DisplaySize128x64: buffered_128x64, terminal_128x64;
+    DisplaySize128x32: buffered_128x32, terminal_128x32;
+    DisplaySize96x16: buffered_96x16, terminal_96x16;
+    DisplaySize72x40: buffered_72x40, terminal_72x40;
+    DisplaySize64x48: buffered_64x48, terminal_64x48;
+    DisplaySize64x32: buffered_64x32, terminal_64x32;
+    DisplaySize128x64Sh1106: buffered_128x64sh1106, terminal_128x64sh1106;
+    DisplaySize128x128Sh1107: buffered_128x128sh1107, terminal_128x128sh1107;
+    DisplaySize64x128Sh1107: buffered_64x128sh1107, terminal_64x128sh1107;
+    DisplaySize128x32Ssd1305: buffered_128x32ssd1305, terminal_128x32ssd1305;
+    DisplaySize128x64Ssd1305: buffered_128x64ssd1305, terminal_128x64ssd1305;
+}
+
+/// A [`DynamicSize`] must show the same image as the fixed size it replaces.
+#[test]
+fn dynamic_128x32() {
+    let size = DynamicSize::new(128, 32).com_pins(false, false);
+    check_rotations("buffered", "128x32", |rotation| {
+        buffered_graphics(size, rotation)
+    });
+    check_rotations("terminal", "128x32", |rotation| terminal(size, rotation));
+}
+
+#[test]
+fn dynamic_64x48() {
+    let size = DynamicSize::new(64, 48).offset(32, 0);
+    check_rotations("buffered", "64x48", |rotation| {
+        buffered_graphics(size, rotation)
+    });
+    check_rotations("terminal", "64x48", |rotation| terminal(size, rotation));
+}
+
+ssd1306::display_size! {
+    /// The same panel as `DisplaySize72x40`, defined with the macro
+    struct MacroSize72x40 {
+        width: 72,
+        height: 40,
+        offset: (28, 0),
+        com_pins: (true, false),
+        iref: (true, true),
+    }
+}
+
+/// A size defined with `display_size!` must show the same image as the equivalent built-in size.
+#[test]
+fn macro_72x40() {
+    check_rotations("buffered", "72x40", |rotation| {
+        buffered_graphics(MacroSize72x40, rotation)
+    });
+    check_rotations("terminal", "72x40", |rotation| {
+        terminal(MacroSize72x40, rotation)
+    });
+}
+
+/// Flip a PBM image left to right, or top to bottom if `vertical` is set.
+fn flip_pbm(pbm: &str, vertical: bool) -> String {
+    let mut lines: Vec<String> = pbm.lines().map(String::from).collect();
+    let pixels = &mut lines[2..];
+    if vertical {
+        pixels.reverse();
+    } else {
+        for row in pixels.iter_mut() {
+            *row = row.chars().rev().collect();
+        }
+    }
+
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// A mirrored display must show the mirrored snapshot, also when it is rotated after it was
+/// mirrored.
+fn check_mirrored<SIZE>(size: SIZE)
+where
+    SIZE: DisplaySize + Copy,
+{
+    for (rotation, name) in ROTATIONS {
+        let mut display = Ssd1306::new(Emulator::for_size(size), size, DisplayRotation::Rotate0)
+            .into_buffered_graphics_mode()
+            .init()
+            .unwrap();
+        display.set_mirror(true).unwrap();
+        display.set_rotation(rotation).unwrap();
+        assert_eq!(
+            display.orientation(),
+            DisplayOrientation::new(rotation, true)
+        );
+        draw_test_pattern(&mut display);
+        let actual = to_pbm(&display.release());
+
+        // Mirroring the rotated display flips the panel along the rotated axis
+        let expected = flip_pbm(
+            &to_pbm(&buffered_graphics(size, rotation)),
+            matches!(
+                rotation,
+                DisplayRotation::Rotate90 | DisplayRotation::Rotate270
+            ),
+        );
+
+        assert_frame(name, &expected, &actual);
+    }
+}
+
+#[test]
+fn mirrored_128x64() {
+    check_mirrored(DisplaySize128x64);
+}
+
+/// The panel is not centered in the driver RAM, so the column offset depends on the mirroring.
+#[test]
+fn mirrored_128x32ssd1305() {
+    check_mirrored(DisplaySize128x32Ssd1305);
+}
+
+#[test]
+fn mirrored_64x128sh1107() {
+    check_mirrored(DisplaySize64x128Sh1107);
+}
+
+/// Changing the rotation of a buffered display must not leave stale content from the previous
+/// rotation on the screen.
+fn check_rerotated<SIZE>(size: SIZE, size_name: &str)
+where
+    SIZE: DisplaySize + Copy,
+{
+    for (rotation, rotation_name) in ROTATIONS {
+        let mut display = Ssd1306::new(Emulator::for_size(size), size, DisplayRotation::Rotate0)
+            .into_buffered_graphics_mode()
+            .init()
+            .unwrap();
+        draw_test_pattern(&mut display);
+        display.set_rotation(rotation).unwrap();
+        draw_test_pattern(&mut display);
+
+        let name = format!("buffered_{}_{}", size_name, rotation_name);
+        if let Err(e) = check_snapshot(&name, &display.release()) {
+            panic!("{}", e);
+        }
+    }
+}
+
+#[test]
+fn rerotated_72x40() {
+    check_rerotated(DisplaySize72x40, "72x40");
+}
+
+#[test]
+fn rerotated_128x32ssd1305() {
+    check_rerotated(DisplaySize128x32Ssd1305, "128x32Ssd1305");
+}
+
+/// Software rotation and mirroring must show the same image as the hardware orientation.
+fn check_software_orientation<SIZE>(size: SIZE)
+where
+    SIZE: DisplaySize + Copy,
+{
+    for (rotation, name) in ROTATIONS {
+        for mirrored in [false, true] {
+            let orientation = DisplayOrientation::new(rotation, mirrored);
+
+            let mut display =
+                Ssd1306::new(Emulator::for_size(size), size, DisplayRotation::Rotate0)
+                    .into_buffered_graphics_mode()
+                    .init()
+                    .unwrap();
+            display.set_orientation(orientation).unwrap();
+            draw_test_pattern(&mut display);
+            let expected = to_pbm(&display.release());
+
+            let mut display =
+                Ssd1306::new(Emulator::for_size(size), size, DisplayRotation::Rotate0)
+                    .into_buffered_graphics_mode()
+                    .init()
+                    .unwrap();
+            display.set_software_orientation(orientation);
+            draw_test_pattern(&mut display);
+            let actual = to_pbm(&display.release());
+
+            assert_frame(
+                &format!("{} mirrored={}", name, mirrored),
+                &expected,
+                &actual,
+            );
+        }
+    }
+}
+
+#[test]
+fn software_orientation_128x64() {
+    check_software_orientation(DisplaySize128x64);
+}
+
+#[test]
+fn software_orientation_128x32ssd1305() {
+    check_software_orientation(DisplaySize128x32Ssd1305);
+}
+
+#[test]
+fn software_orientation_64x128sh1107() {
+    check_software_orientation(DisplaySize64x128Sh1107);
+}
+
+/// Filling rectangles must change the same pixels as drawing them pixel by pixel.
+#[test]
+fn fill() {
+    let size = DisplaySize128x32Ssd1305;
+    let areas = [
+        Rectangle::new(Point::new(3, 5), Size::new(20, 19)),
+        Rectangle::new(Point::new(-4, 9), Size::new(9, 1)),
+        Rectangle::new(Point::new(10, -3), Size::new(12, 7)),
+        Rectangle::new(Point::new(30, 0), Size::new(1, 200)),
+        Rectangle::new(Point::new(60, 16), Size::new(70, 8)),
+        Rectangle::new(Point::new(200, 16), Size::new(7, 8)),
+    ];
+    let color = |i: usize| BinaryColor::from(i % 3 == 0);
+
+    for (rotation, name) in ROTATIONS {
+        for (software_rotation, software_name) in ROTATIONS {
+            for mirrored in [false, true] {
+                let software = DisplayOrientation::new(software_rotation, mirrored);
+                let mut expected = Ssd1306::new(Emulator::for_size(size), size, rotation)
+                    .into_buffered_graphics_mode()
+                    .init()
+                    .unwrap();
+                let mut actual = Ssd1306::new(Emulator::for_size(size), size, rotation)
+                    .into_buffered_graphics_mode()
+                    .init()
+                    .unwrap();
+                expected.set_software_orientation(software);
+                actual.set_software_orientation(software);
+
+                for area in areas {
+                    expected
+                        .draw_iter(area.points().map(|p| Pixel(p, BinaryColor::On)))
+                        .unwrap();
+                    actual.fill_solid(&area, BinaryColor::On).unwrap();
+
+                    let inner = area.offset(-1);
+                    expected
+                        .draw_iter(inner.points().enumerate().map(|(i, p)| Pixel(p, color(i))))
+                        .unwrap();
+                    actual.fill_contiguous(&inner, (0..).map(color)).unwrap();
+
+                    expected.flush().unwrap();
+                    actual.flush().unwrap();
+                }
+
+                let expected = to_pbm(&expected.release());
+                let actual = to_pbm(&actual.release());
+                assert_frame(
+                    &format!("{} software={} mirrored={}", name, software_name, mirrored),
+                    &expected,
+                    &actual,
+                );
+            }
+        }
+    }
+}
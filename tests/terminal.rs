@@ -0,0 +1,54 @@
+//! Terminal mode on displays whose width is not a multiple of the character width.
+
+use ssd1306::{emulator::Emulator, mode::TerminalModeError, prelude::*, Ssd1306};
+
+#[test]
+fn clear_partial_character() {
+    let size = DynamicSize::new(100, 16);
+
+    // Fill the display RAM
+    let mut display = Ssd1306::new(Emulator::for_size(size), size, DisplayRotation::Rotate0)
+        .into_buffered_graphics_mode()
+        .init()
+        .unwrap();
+    for x in 0..100 {
+        for y in 0..16 {
+            display.set_pixel(x, y, true);
+        }
+    }
+    display.flush().unwrap();
+    let emulator = display.release();
+    assert!(emulator.pixel(99, 15));
+
+    for rotation in [DisplayRotation::Rotate0, DisplayRotation::Rotate90] {
+        let mut display = Ssd1306::new(emulator.clone(), size, rotation)
+            .into_terminal_mode()
+            .init()
+            .unwrap();
+        display.clear().unwrap();
+
+        let emulator = display.release();
+        for x in 0..100 {
+            for y in 0..16 {
+                assert!(!emulator.pixel(x, y), "{:?} ({}, {})", rotation, x, y);
+            }
+        }
+    }
+}
+
+#[test]
+fn narrow_display() {
+    let size = DynamicSize::new(4, 16);
+
+    for rotation in [DisplayRotation::Rotate0, DisplayRotation::Rotate90] {
+        let result = Ssd1306::new(Emulator::for_size(size), size, rotation)
+            .into_terminal_mode()
+            .init();
+        assert!(
+            matches!(result, Err(TerminalModeError::OutOfBounds)),
+            "{:?} {:?}",
+            rotation,
+            result.err()
+        );
+    }
+}